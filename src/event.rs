@@ -44,7 +44,7 @@ use crate::{
   dpi::{PhysicalPosition, PhysicalSize},
   keyboard::{self, ModifiersState},
   platform_impl,
-  window::{Theme, WindowId},
+  window::{Theme, WindowId, WindowMode},
 };
 
 /// Describes a generic event.
@@ -110,6 +110,9 @@ pub enum Event<'a, T: 'static> {
   /// Mainly of interest to applications with mostly-static graphics that avoid redrawing unless
   /// something changes, like most non-game GUIs.
   ///
+  /// A window always receives this event at least once after it's shown, so the first frame
+  /// can be drawn without calling [`Window::request_redraw`](crate::window::Window::request_redraw).
+  ///
   /// ## Platform-specific
   ///
   /// - **Linux: This is triggered by `draw` signal of the gtk window. It can be used to detect if
@@ -143,6 +146,28 @@ pub enum Event<'a, T: 'static> {
   /// - **Other**: Unsupported.
   #[non_exhaustive]
   Reopen { has_visible_windows: bool },
+
+  /// Emitted when the user session ends, because the user logs out or the system shuts down or
+  /// restarts.
+  ///
+  /// It's first emitted with `ending: false` when the system asks whether the session can end.
+  /// It is cancelled if a window has a shutdown block reason set with
+  /// [`WindowExtWindows::set_shutdown_block_reason`] once the event has been handled. The event
+  /// is delivered after the system got its answer if it arrives while the event loop is busy,
+  /// e.g. in a modal loop, so a reason set beforehand is the only reliable way to block it.
+  ///
+  /// It's then emitted with `ending: true` if the session actually ends. The process can be
+  /// terminated as soon as the event has been handled, without [`Event::LoopDestroyed`], so
+  /// unsaved data must be saved right away.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** Emitted for `WM_QUERYENDSESSION` and `WM_ENDSESSION`.
+  /// - **macOS / Linux / iOS / Android:** Unsupported.
+  ///
+  /// [`WindowExtWindows::set_shutdown_block_reason`]: crate::platform::windows::WindowExtWindows::set_shutdown_block_reason
+  #[non_exhaustive]
+  SessionEnd { ending: bool },
 }
 
 impl<T: Clone> Clone for Event<'static, T> {
@@ -171,6 +196,7 @@ impl<T: Clone> Clone for Event<'static, T> {
       } => Reopen {
         has_visible_windows: *has_visible_windows,
       },
+      SessionEnd { ending } => SessionEnd { ending: *ending },
     }
   }
 }
@@ -195,6 +221,7 @@ impl<'a, T> Event<'a, T> {
       } => Ok(Reopen {
         has_visible_windows,
       }),
+      SessionEnd { ending } => Ok(SessionEnd { ending }),
     }
   }
 
@@ -221,6 +248,7 @@ impl<'a, T> Event<'a, T> {
       } => Some(Reopen {
         has_visible_windows,
       }),
+      SessionEnd { ending } => Some(SessionEnd { ending }),
     }
   }
 }
@@ -259,6 +287,10 @@ pub enum StartCause {
 #[derive(Debug, PartialEq)]
 pub enum WindowEvent<'a> {
   /// The size of the window has changed. Contains the client area's new dimensions.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** Not emitted when the window is minimized, see [`WindowEvent::Minimized`] instead.
   Resized(PhysicalSize<u32>),
 
   /// The position of the window has changed. Contains the window's new position.
@@ -269,6 +301,19 @@ pub enum WindowEvent<'a> {
   Moved(PhysicalPosition<i32>),
 
   /// The window has been requested to close.
+  ///
+  /// The window is only closed once it is dropped, so ignoring this event keeps it open. To keep
+  /// the application running in the background instead, e.g. behind a tray icon, hide the window
+  /// with [`Window::set_visible(false)`](crate::window::Window::set_visible), which also removes
+  /// it from the taskbar, and bring it back with
+  /// [`Window::activate`](crate::window::Window::activate), which shows, restores and focuses it.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS:** The dock icon stays when all windows are hidden. Use
+  ///   `EventLoopWindowTargetExtMacOS::set_activation_policy_at_runtime` with
+  ///   `ActivationPolicy::Accessory` to remove it, and switch back to `ActivationPolicy::Regular`
+  ///   before showing the window again.
   CloseRequested,
 
   /// The window has been destroyed.
@@ -283,6 +328,11 @@ pub enum WindowEvent<'a> {
   ///
   /// When the user drops multiple files at once, this event will be emitted for each file
   /// separately.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Dropped URIs with the `file` scheme are reported as this event with their local
+  ///   path. URIs with any other scheme are reported as [`WindowEvent::DroppedUrl`].
   DroppedFile(PathBuf),
 
   /// A file is being hovered over the window.
@@ -297,6 +347,23 @@ pub enum WindowEvent<'a> {
   /// hovered.
   HoveredFileCancelled,
 
+  /// Text has been dropped into the window.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS / Android:** Unsupported.
+  DroppedText(String),
+
+  /// A URL, such as a link dragged from a browser, has been dropped into the window.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Only URIs with a scheme other than `file` are reported as this event. `file://`
+  ///   URIs that map to a local path are reported as [`WindowEvent::DroppedFile`] instead, like
+  ///   dropped files on Windows and macOS.
+  /// - **iOS / Android:** Unsupported.
+  DroppedUrl(url::Url),
+
   /// The window received a unicode character.
   ReceivedImeText(String),
 
@@ -378,6 +445,23 @@ pub enum WindowEvent<'a> {
     stage: i64,
   },
 
+  /// Touchpad swipe gesture event.
+  ///
+  /// `delta` is the direction of the swipe, each component being `-1.0`, `0.0` or `1.0`. Positive
+  /// values mean a swipe to the right or downwards, following the sign convention of
+  /// `MouseScrollDelta`. This is typically used for back and forward navigation.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS:** Only sent for the swipes configured as "Swipe between pages" in the trackpad
+  ///   settings with three fingers. Two-finger swipes are delivered as `WindowEvent::MouseWheel`.
+  /// - **Windows / Linux / iOS / Android:** Unsupported.
+  TouchpadSwipe {
+    device_id: DeviceId,
+    delta: (f64, f64),
+    phase: TouchPhase,
+  },
+
   /// Motion on some analog axis. May report data redundant to other, more specific events.
   AxisMotion {
     device_id: DeviceId,
@@ -400,7 +484,16 @@ pub enum WindowEvent<'a> {
   /// is pointed to by the `new_inner_size` reference. By default, this will contain the size suggested
   /// by the OS, but it can be changed to any value.
   ///
+  /// [`Window::scale_factor`](crate::window::Window::scale_factor) already returns the new scale
+  /// factor when this event is received, and it is always followed by a [`WindowEvent::Resized`]
+  /// with the resulting inner size, even if the size didn't change. Sizes reported by `Resized`
+  /// are thus never computed with a stale scale factor.
+  ///
   /// For more information about DPI in general, see the [`dpi`](crate::dpi) module.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android:** Unsupported.
   ScaleFactorChanged {
     scale_factor: f64,
     new_inner_size: &'a mut PhysicalSize<u32>,
@@ -411,9 +504,12 @@ pub enum WindowEvent<'a> {
   /// Applications might wish to react to this to change the theme of the content of the window
   /// when the system changes the window theme.
   ///
+  /// [`Window::theme`](crate::window::Window::theme) already returns the new theme when this event
+  /// is received.
+  ///
   /// ## Platform-specific
   ///
-  /// - **Linux / Android / iOS:** Unsupported
+  /// - **Android / iOS:** Unsupported
   ThemeChanged(Theme),
 
   /// The window decorations has been clicked.
@@ -422,6 +518,45 @@ pub enum WindowEvent<'a> {
   ///
   /// - **Linux / macOS / Android / iOS:** Unsupported
   DecorationsClick,
+
+  /// The window has been minimized (`true`) or restored from being minimized (`false`).
+  ///
+  /// Emitted both when minimizing through [`Window::set_minimized`](crate::window::Window::set_minimized)
+  /// and through the window decorations or the system.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Not emitted on Wayland as compositors don't report the minimized state.
+  /// - **Android / iOS:** Unsupported
+  Minimized(bool),
+
+  /// The window has been occluded (`true`) or is visible again (`false`).
+  ///
+  /// An occluded window can't be seen by the user, so applications can use this to stop
+  /// rendering it until it becomes visible again.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS:** Also emitted when the window is fully covered by other windows or is on another
+  ///   space.
+  /// - **Windows:** Only emitted when the window is minimized or restored, being covered by other
+  ///   windows isn't reported.
+  /// - **Linux:** Only emitted when the window is minimized, hidden or shown again, being covered
+  ///   by other windows isn't reported. On Wayland, minimizing isn't reported either.
+  /// - **Android / iOS:** Unsupported
+  Occluded(bool),
+
+  /// The window switched between being shown normally, maximized, fullscreen or minimized.
+  ///
+  /// This is meant to persist and restore the window mode, and is emitted whether the change was
+  /// requested by the application, the user or the system.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Minimizing isn't reported on Wayland as compositors don't report it.
+  /// - **macOS:** Maximizing windows without decorations or that aren't resizable is not reported.
+  /// - **Android / iOS:** Unsupported
+  WindowModeChanged(WindowMode),
 }
 
 impl Clone for WindowEvent<'static> {
@@ -435,6 +570,8 @@ impl Clone for WindowEvent<'static> {
       DroppedFile(file) => DroppedFile(file.clone()),
       HoveredFile(file) => HoveredFile(file.clone()),
       HoveredFileCancelled => HoveredFileCancelled,
+      DroppedText(text) => DroppedText(text.clone()),
+      DroppedUrl(url) => DroppedUrl(url.clone()),
       ReceivedImeText(c) => ReceivedImeText(c.clone()),
       Focused(f) => Focused(*f),
       KeyboardInput {
@@ -497,6 +634,15 @@ impl Clone for WindowEvent<'static> {
         pressure: *pressure,
         stage: *stage,
       },
+      TouchpadSwipe {
+        device_id,
+        delta,
+        phase,
+      } => TouchpadSwipe {
+        device_id: *device_id,
+        delta: *delta,
+        phase: *phase,
+      },
       AxisMotion {
         device_id,
         axis,
@@ -512,6 +658,9 @@ impl Clone for WindowEvent<'static> {
         unreachable!("Static event can't be about scale factor changing")
       }
       DecorationsClick => DecorationsClick,
+      Minimized(minimized) => Minimized(*minimized),
+      Occluded(occluded) => Occluded(*occluded),
+      WindowModeChanged(mode) => WindowModeChanged(*mode),
     };
   }
 }
@@ -527,6 +676,8 @@ impl<'a> WindowEvent<'a> {
       DroppedFile(file) => Some(DroppedFile(file)),
       HoveredFile(file) => Some(HoveredFile(file)),
       HoveredFileCancelled => Some(HoveredFileCancelled),
+      DroppedText(text) => Some(DroppedText(text)),
+      DroppedUrl(url) => Some(DroppedUrl(url)),
       ReceivedImeText(c) => Some(ReceivedImeText(c)),
       Focused(focused) => Some(Focused(focused)),
       KeyboardInput {
@@ -584,6 +735,15 @@ impl<'a> WindowEvent<'a> {
         pressure,
         stage,
       }),
+      TouchpadSwipe {
+        device_id,
+        delta,
+        phase,
+      } => Some(TouchpadSwipe {
+        device_id,
+        delta,
+        phase,
+      }),
       AxisMotion {
         device_id,
         axis,
@@ -597,6 +757,9 @@ impl<'a> WindowEvent<'a> {
       ThemeChanged(theme) => Some(ThemeChanged(theme)),
       ScaleFactorChanged { .. } => None,
       DecorationsClick => Some(DecorationsClick),
+      Minimized(minimized) => Some(Minimized(minimized)),
+      Occluded(occluded) => Some(Occluded(occluded)),
+      WindowModeChanged(mode) => Some(WindowModeChanged(mode)),
     }
   }
 }
@@ -14,15 +14,24 @@
 //! [event_loop_proxy]: crate::event_loop::EventLoopProxy
 //! [send_event]: crate::event_loop::EventLoopProxy::send_event
 use instant::Instant;
-use std::{error, fmt, marker::PhantomData, ops::Deref};
+use std::{
+  cell::{Cell, RefCell},
+  collections::{HashMap, HashSet},
+  error, fmt,
+  marker::PhantomData,
+  ops::Deref,
+  rc::Rc,
+  sync::{Arc, Mutex},
+  time::Duration,
+};
 
 use crate::{
-  dpi::PhysicalPosition,
+  dpi::{PhysicalPosition, PhysicalSize},
   error::ExternalError,
-  event::Event,
+  event::{Event, WindowEvent},
   monitor::MonitorHandle,
   platform_impl,
-  window::{ProgressBarState, Theme},
+  window::{ProgressBarState, Theme, Window, WindowId},
 };
 
 /// Provides a way to retrieve events from the system and from the windows that were registered to
@@ -49,12 +58,244 @@ pub struct EventLoop<T: 'static> {
 /// your callback. `EventLoop` will coerce into this type (`impl<T> Deref for
 /// EventLoop<T>`), so functions that take this as a parameter can also take
 /// `&EventLoop`.
+///
+/// Clones share the state of the running event loop, such as its control flow.
 #[derive(Clone)]
 pub struct EventLoopWindowTarget<T: 'static> {
   pub(crate) p: platform_impl::EventLoopWindowTarget<T>,
+  pub(crate) control_flow: Rc<StoredControlFlow>,
+  pub(crate) busy_cursor_depth: Rc<Cell<u32>>,
+  pub(crate) resize_debouncer: Rc<ResizeDebouncer>,
+  #[cfg(feature = "trace")]
+  pub(crate) stats: Rc<Cell<EventLoopStats>>,
   pub(crate) _marker: ::std::marker::PhantomData<*mut ()>, // Not Send nor Sync
 }
 
+/// Counters describing the activity of the event loop, returned by
+/// [`EventLoopWindowTarget::stats`].
+///
+/// A loop with few wakeups while events keep piling up is starved, one with a growing number of
+/// wakeups and redraws while idle is spinning.
+#[cfg(feature = "trace")]
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EventLoopStats {
+  /// Number of times the event loop woke up, i.e. sent `Event::NewEvents`.
+  pub wakeups: u64,
+  /// Number of `Event::RedrawRequested` sent.
+  pub redraws: u64,
+  /// Number of events sent to the event handler, including the ones counted above.
+  pub events: u64,
+  /// Number of events that were waiting to be handled when the event loop last woke up.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / iOS / Android:** Unsupported, always `0`.
+  pub queue_depth: usize,
+  /// Largest `queue_depth` seen so far.
+  pub max_queue_depth: usize,
+}
+
+/// The control flow seen and set through [`EventLoopWindowTarget::control_flow`] and
+/// [`EventLoopWindowTarget::set_control_flow`].
+pub(crate) struct StoredControlFlow {
+  current: Cell<ControlFlow>,
+  pending: Cell<Option<ControlFlow>>,
+}
+
+impl Default for StoredControlFlow {
+  fn default() -> Self {
+    Self {
+      current: Cell::new(ControlFlow::Wait),
+      pending: Cell::new(None),
+    }
+  }
+}
+
+/// Coalesces the `WindowEvent::Resized` of the windows that opted in with
+/// [`Window::set_resize_debounce`](crate::window::Window::set_resize_debounce).
+#[derive(Clone, Default)]
+pub(crate) struct ResizeDebouncer {
+  /// The debounce duration of each window, shared with the windows so they can change it.
+  pub(crate) debounces: Arc<Mutex<HashMap<WindowId, Duration>>>,
+  pending: RefCell<HashMap<WindowId, PendingResize>>,
+  /// The windows whose `ScaleFactorChanged` was delivered, with their following `Resized` not yet.
+  scale_factor_changed: RefCell<HashSet<WindowId>>,
+}
+
+#[derive(Clone, Copy)]
+struct PendingResize {
+  debounce: Duration,
+  last_delivered: Instant,
+  last_received: Instant,
+  /// The latest size that was held back, if any.
+  size: Option<PhysicalSize<u32>>,
+}
+
+impl ResizeDebouncer {
+  /// Returns whether `event` should be delivered now. `Resized` events received less than the
+  /// debounce duration after the last delivered one are held back, except the one following a
+  /// `ScaleFactorChanged`, which is always delivered right away.
+  fn should_deliver<T>(&self, event: &Event<'_, T>) -> bool {
+    let (window_id, size) = match event {
+      Event::WindowEvent {
+        window_id,
+        event: WindowEvent::Resized(size),
+      } => (*window_id, *size),
+      Event::WindowEvent {
+        window_id,
+        event: WindowEvent::ScaleFactorChanged { .. },
+      } => {
+        self.scale_factor_changed.borrow_mut().insert(*window_id);
+        return true;
+      }
+      Event::WindowEvent {
+        window_id,
+        event: WindowEvent::Destroyed,
+      } => {
+        self.pending.borrow_mut().remove(window_id);
+        self.scale_factor_changed.borrow_mut().remove(window_id);
+        return true;
+      }
+      _ => return true,
+    };
+    let follows_scale_factor_change = self.scale_factor_changed.borrow_mut().remove(&window_id);
+    let debounce = match self.debounces.lock().unwrap().get(&window_id) {
+      Some(debounce) => *debounce,
+      None => return true,
+    };
+
+    let now = Instant::now();
+    let mut pending = self.pending.borrow_mut();
+    match pending.get_mut(&window_id) {
+      Some(resize) if !follows_scale_factor_change && now < resize.last_delivered + debounce => {
+        resize.last_received = now;
+        resize.size = Some(size);
+        false
+      }
+      _ => {
+        pending.insert(
+          window_id,
+          PendingResize {
+            debounce,
+            last_delivered: now,
+            last_received: now,
+            size: None,
+          },
+        );
+        true
+      }
+    }
+  }
+
+  /// Takes the held back sizes of the windows that haven't been resized for their debounce
+  /// duration.
+  fn take_settled(&self) -> Vec<(WindowId, PhysicalSize<u32>)> {
+    let mut pending = self.pending.borrow_mut();
+    if pending.is_empty() {
+      return Vec::new();
+    }
+
+    let now = Instant::now();
+    let mut settled = Vec::new();
+    pending.retain(|window_id, resize| match resize.size {
+      Some(size) if now >= resize.last_received + resize.debounce => {
+        settled.push((*window_id, size));
+        false
+      }
+      Some(_) => true,
+      None => now < resize.last_delivered + resize.debounce,
+    });
+    settled
+  }
+
+  /// Makes sure the event loop wakes up to deliver the held back `Resized` events.
+  fn limit_control_flow(&self, control_flow: &mut ControlFlow) {
+    let deadline = self
+      .pending
+      .borrow()
+      .values()
+      .filter(|resize| resize.size.is_some())
+      .map(|resize| resize.last_received + resize.debounce)
+      .min();
+    if let Some(deadline) = deadline {
+      match *control_flow {
+        ControlFlow::Wait => *control_flow = ControlFlow::WaitUntil(deadline),
+        ControlFlow::WaitUntil(resume) if resume > deadline => {
+          *control_flow = ControlFlow::WaitUntil(deadline)
+        }
+        _ => (),
+      }
+    }
+  }
+}
+
+/// Wraps `event_handler` so that the control flow set with
+/// [`EventLoopWindowTarget::set_control_flow`] is applied once it returns, and the `Resized`
+/// events of the windows with a resize debounce are coalesced.
+pub(crate) fn wrap_event_handler<T, F>(
+  mut event_handler: F,
+) -> impl FnMut(Event<'_, T>, &EventLoopWindowTarget<T>, &mut ControlFlow)
+where
+  F: FnMut(Event<'_, T>, &EventLoopWindowTarget<T>, &mut ControlFlow),
+{
+  move |event, target, control_flow| {
+    let mut dispatch = |event: Event<'_, T>, control_flow: &mut ControlFlow| {
+      #[cfg(feature = "trace")]
+      let previous_flow = {
+        target.record_event(&event);
+        *control_flow
+      };
+      target.control_flow.current.set(*control_flow);
+      event_handler(event, target, control_flow);
+      if let Some(flow) = target.control_flow.pending.take() {
+        *control_flow = flow;
+      }
+      #[cfg(feature = "trace")]
+      if *control_flow != previous_flow {
+        trace!(
+          "Control flow changed from {:?} to {:?}",
+          previous_flow,
+          control_flow
+        );
+      };
+    };
+    let settled_events = || {
+      target
+        .resize_debouncer
+        .take_settled()
+        .into_iter()
+        .map(|(window_id, size)| Event::WindowEvent {
+          window_id,
+          event: WindowEvent::Resized(size),
+        })
+    };
+
+    // Settled sizes are delivered with the other window events, between `NewEvents` and
+    // `MainEventsCleared`.
+    match event {
+      Event::NewEvents(_) => {
+        dispatch(event, control_flow);
+        for event in settled_events() {
+          dispatch(event, control_flow);
+        }
+      }
+      Event::MainEventsCleared => {
+        for event in settled_events() {
+          dispatch(event, control_flow);
+        }
+        dispatch(event, control_flow);
+      }
+      event => {
+        if target.resize_debouncer.should_deliver(&event) {
+          dispatch(event, control_flow);
+        }
+      }
+    }
+    target.resize_debouncer.limit_control_flow(control_flow);
+  }
+}
+
 impl<T> fmt::Debug for EventLoop<T> {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     f.pad("EventLoop { .. }")
@@ -175,6 +416,122 @@ impl Default for ControlFlow {
   }
 }
 
+/// Paces the redraws of a window at a fixed frame rate, using [`ControlFlow::WaitUntil`] to wake
+/// up the event loop for each frame.
+///
+/// Frames stay on a fixed time grid so the frame rate doesn't drift. When a frame is rendered
+/// late, the frames that were missed in the meantime are skipped rather than rendered in a burst.
+///
+/// ```no_run
+/// use tao::{
+///   event::{Event, WindowEvent},
+///   event_loop::{AnimationTimer, ControlFlow, EventLoop},
+///   window::WindowBuilder,
+/// };
+///
+/// let event_loop = EventLoop::new();
+/// let window = WindowBuilder::new().build(&event_loop).unwrap();
+/// let mut timer = AnimationTimer::new(60.0);
+///
+/// event_loop.run(move |event, _, control_flow| {
+///   *control_flow = ControlFlow::Wait;
+///   match event {
+///     Event::MainEventsCleared => {
+///       timer.tick(&window, control_flow);
+///     }
+///     Event::RedrawRequested(_) => {
+///       // draw the next frame
+///     }
+///     Event::WindowEvent {
+///       event: WindowEvent::CloseRequested,
+///       ..
+///     } => *control_flow = ControlFlow::Exit,
+///     _ => (),
+///   }
+/// });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnimationTimer {
+  frame_duration: Duration,
+  next_frame: Instant,
+}
+
+impl AnimationTimer {
+  /// Creates a timer running at `frames_per_second`, with its first frame due right away.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `frames_per_second` isn't a positive number.
+  pub fn new(frames_per_second: f64) -> Self {
+    assert!(
+      frames_per_second > 0.0,
+      "the frame rate of an `AnimationTimer` must be positive"
+    );
+    Self::with_frame_duration(Duration::from_secs_f64(1.0 / frames_per_second))
+  }
+
+  /// Creates a timer with `frame_duration` between two frames, with its first frame due right
+  /// away.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `frame_duration` is zero.
+  pub fn with_frame_duration(frame_duration: Duration) -> Self {
+    assert!(
+      !frame_duration.is_zero(),
+      "the frame duration of an `AnimationTimer` can't be zero"
+    );
+    Self {
+      frame_duration,
+      next_frame: Instant::now(),
+    }
+  }
+
+  /// Returns the duration between two frames.
+  pub fn frame_duration(&self) -> Duration {
+    self.frame_duration
+  }
+
+  /// Returns when the next frame is due.
+  pub fn next_frame(&self) -> Instant {
+    self.next_frame
+  }
+
+  /// Returns whether a frame is due at `now`, in which case the next frame is scheduled.
+  pub fn advance(&mut self, now: Instant) -> bool {
+    if now < self.next_frame {
+      return false;
+    }
+
+    // Skip the frames that were missed, without leaving the frame grid.
+    let frame = self.frame_duration.as_nanos();
+    let late = (now - self.next_frame).as_nanos() % frame;
+    self.next_frame = now + Duration::from_nanos((frame - late) as u64);
+    true
+  }
+
+  /// Requests a redraw of `window` if a frame is due, and makes sure `control_flow` wakes up the
+  /// event loop for the next frame. Returns whether a redraw was requested.
+  ///
+  /// This is meant to be called once per event loop iteration, e.g. on
+  /// [`Event::MainEventsCleared`], after the control flow was set. [`ControlFlow::Poll`] and
+  /// [`ControlFlow::ExitWithCode`] are left as they are.
+  pub fn tick(&mut self, window: &Window, control_flow: &mut ControlFlow) -> bool {
+    let due = self.advance(Instant::now());
+    if due {
+      window.request_redraw();
+    }
+    match *control_flow {
+      ControlFlow::Wait => *control_flow = ControlFlow::WaitUntil(self.next_frame),
+      ControlFlow::WaitUntil(resume) if resume > self.next_frame => {
+        *control_flow = ControlFlow::WaitUntil(self.next_frame)
+      }
+      _ => (),
+    }
+    due
+  }
+}
+
 impl EventLoop<()> {
   /// Alias for [`EventLoopBuilder::new().build()`].
   ///
@@ -212,7 +569,7 @@ impl<T> EventLoop<T> {
   where
     F: 'static + FnMut(Event<'_, T>, &EventLoopWindowTarget<T>, &mut ControlFlow),
   {
-    self.event_loop.run(event_handler)
+    self.event_loop.run(wrap_event_handler(event_handler))
   }
 
   /// Creates an `EventLoopProxy` that can be used to dispatch user events to the main event loop.
@@ -231,6 +588,68 @@ impl<T> Deref for EventLoop<T> {
 }
 
 impl<T> EventLoopWindowTarget<T> {
+  /// Returns the control flow the event loop will use once the current event handler returns.
+  ///
+  /// This reflects the `control_flow` parameter as it was when the event handler was called,
+  /// or the last value passed to [`EventLoopWindowTarget::set_control_flow`].
+  #[inline]
+  pub fn control_flow(&self) -> ControlFlow {
+    self.control_flow.current.get()
+  }
+
+  /// Sets the control flow of the event loop.
+  ///
+  /// This is an alternative to writing to the `control_flow` parameter of the event handler,
+  /// useful from code that only has access to the window target. The value is applied when the
+  /// current event handler returns and takes precedence over the parameter.
+  #[inline]
+  pub fn set_control_flow(&self, control_flow: ControlFlow) {
+    self.control_flow.current.set(control_flow);
+    self.control_flow.pending.set(Some(control_flow));
+  }
+
+  /// Returns the counters collected since the event loop was created.
+  ///
+  /// This is only available with the `trace` feature.
+  #[cfg(feature = "trace")]
+  #[inline]
+  pub fn stats(&self) -> EventLoopStats {
+    self.stats.get()
+  }
+
+  #[cfg(feature = "trace")]
+  fn record_event(&self, event: &Event<'_, T>) {
+    let mut stats = self.stats.get();
+    stats.events += 1;
+    match event {
+      Event::NewEvents(cause) => {
+        stats.wakeups += 1;
+        trace!("Event loop woke up: {:?}", cause);
+      }
+      Event::RedrawRequested(_) => stats.redraws += 1,
+      _ => (),
+    }
+    self.stats.set(stats);
+  }
+
+  /// Records the number of events waiting to be handled when the event loop woke up.
+  #[cfg(all(
+    feature = "trace",
+    any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd"
+    )
+  ))]
+  pub(crate) fn record_queue_depth(&self, depth: usize) {
+    let mut stats = self.stats.get();
+    stats.queue_depth = depth;
+    stats.max_queue_depth = stats.max_queue_depth.max(depth);
+    self.stats.set(stats);
+  }
+
   /// Returns the list of all the monitors available on the system.
   #[inline]
   pub fn available_monitors(&self) -> impl Iterator<Item = MonitorHandle> {
@@ -249,6 +668,20 @@ impl<T> EventLoopWindowTarget<T> {
     self.p.primary_monitor()
   }
 
+  /// Returns the monitor of the focused window, or the primary monitor if there is none.
+  ///
+  /// This can be used to open new windows on the monitor the user is working on.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** The focused window can belong to another application.
+  /// - **Linux:** Only the windows of this application are considered.
+  /// - **iOS / Android:** Always returns the primary monitor.
+  #[inline]
+  pub fn current_monitor(&self) -> Option<MonitorHandle> {
+    self.p.current_monitor()
+  }
+
   /// Returns the monitor that contains the given point.
   ///
   /// ## Platform-specific:
@@ -301,8 +734,86 @@ impl<T> EventLoopWindowTarget<T> {
     self.p.set_progress_bar(_progress)
   }
 
+  /// Shows a busy cursor over all the windows of the application, in place of their own
+  /// cursor, until a matching [`EventLoopWindowTarget::pop_busy_cursor`] call.
+  ///
+  /// Calls nest, so the cursor is only restored once every push has been popped.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS / Android:** Unsupported.
+  #[inline]
+  pub fn push_busy_cursor(&self) {
+    let depth = self.busy_cursor_depth.get();
+    self.busy_cursor_depth.set(depth + 1);
+    #[cfg(any(
+      windows,
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+      target_os = "macos",
+    ))]
+    if depth == 0 {
+      self.p.set_busy_cursor(true);
+    }
+  }
+
+  /// Undoes the last [`EventLoopWindowTarget::push_busy_cursor`] call, restoring the windows'
+  /// own cursors once none is left. Does nothing if the busy cursor isn't shown.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS / Android:** Unsupported.
+  #[inline]
+  pub fn pop_busy_cursor(&self) {
+    let depth = self.busy_cursor_depth.get();
+    self.busy_cursor_depth.set(depth.saturating_sub(1));
+    #[cfg(any(
+      windows,
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+      target_os = "macos",
+    ))]
+    if depth == 1 {
+      self.p.set_busy_cursor(false);
+    }
+  }
+
+  /// Returns the current theme of the application, without needing a window.
+  ///
+  /// This is the theme set with [`EventLoopWindowTarget::set_theme`], or the system theme if
+  /// none was set. It can be used to pick the theme of the first window, e.g. for splash screens.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS / Android:** Unsupported, always returns [`Theme::Light`].
+  #[inline]
+  pub fn theme(&self) -> Theme {
+    #[cfg(any(
+      windows,
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+      target_os = "macos",
+    ))]
+    let theme = self.p.theme();
+    #[cfg(any(target_os = "ios", target_os = "android"))]
+    let theme = Theme::Light;
+    theme
+  }
+
   /// Sets the theme for the application.
   ///
+  /// This can be called before any window is created, windows without a theme of their own
+  /// follow it.
+  ///
   /// ## Platform-specific
   ///
   /// - **iOS / Android:** Unsupported.
@@ -410,3 +921,88 @@ impl Default for DeviceEventFilter {
     Self::Unfocused
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const FRAME: Duration = Duration::from_millis(10);
+
+  #[test]
+  fn first_frame_is_due_right_away() {
+    let mut timer = AnimationTimer::with_frame_duration(FRAME);
+    let start = timer.next_frame();
+
+    assert!(timer.advance(start));
+    assert_eq!(timer.next_frame(), start + FRAME);
+    assert!(!timer.advance(start + FRAME / 2));
+  }
+
+  #[test]
+  fn late_frames_keep_the_frame_grid() {
+    let mut timer = AnimationTimer::with_frame_duration(FRAME);
+    let start = timer.next_frame();
+    timer.advance(start);
+
+    // rendered 3ms late, the next frame is still due on the grid
+    assert!(timer.advance(start + FRAME + Duration::from_millis(3)));
+    assert_eq!(timer.next_frame(), start + FRAME * 2);
+  }
+
+  #[test]
+  fn missed_frames_are_skipped() {
+    let mut timer = AnimationTimer::with_frame_duration(FRAME);
+    let start = timer.next_frame();
+    timer.advance(start);
+
+    assert!(timer.advance(start + FRAME * 4 + Duration::from_millis(5)));
+    assert_eq!(timer.next_frame(), start + FRAME * 5);
+    assert!(!timer.advance(start + FRAME * 4 + Duration::from_millis(6)));
+  }
+
+  #[test]
+  fn frame_rate_sets_the_frame_duration() {
+    let timer = AnimationTimer::new(50.0);
+    assert_eq!(timer.frame_duration(), Duration::from_millis(20));
+  }
+
+  #[test]
+  #[should_panic]
+  fn zero_frame_rate_panics() {
+    AnimationTimer::new(0.0);
+  }
+
+  #[test]
+  fn resized_after_scale_factor_changed_bypasses_the_debounce() {
+    let debouncer = ResizeDebouncer::default();
+    let window_id = unsafe { WindowId::dummy() };
+    debouncer
+      .debounces
+      .lock()
+      .unwrap()
+      .insert(window_id, Duration::from_secs(60));
+    let resized = |width, height| -> Event<'static, ()> {
+      Event::WindowEvent {
+        window_id,
+        event: WindowEvent::Resized(PhysicalSize::new(width, height)),
+      }
+    };
+
+    assert!(debouncer.should_deliver(&resized(100, 100)));
+    assert!(!debouncer.should_deliver(&resized(200, 200)));
+
+    let mut new_inner_size = PhysicalSize::new(400, 400);
+    let scale_factor_changed: Event<'_, ()> = Event::WindowEvent {
+      window_id,
+      event: WindowEvent::ScaleFactorChanged {
+        scale_factor: 2.0,
+        new_inner_size: &mut new_inner_size,
+      },
+    };
+    assert!(debouncer.should_deliver(&scale_factor_changed));
+    assert!(debouncer.should_deliver(&resized(400, 400)));
+    // the size held back before is superseded, and the following sizes are debounced again
+    assert!(debouncer.take_settled().is_empty());
+    assert!(!debouncer.should_deliver(&resized(500, 500)));
+  }
+}
@@ -161,9 +161,45 @@ impl Icon {
   ///
   /// The length of `rgba` must be divisible by 4, and `width * height` must equal
   /// `rgba.len() / 4`. Otherwise, this will return a `BadIcon` error.
+  ///
+  /// The pixels are expected to use straight (non-premultiplied) alpha, which is what image
+  /// decoders like the `image` crate produce. Passing premultiplied data results in dark edges
+  /// around the semi-transparent parts of the icon.
+  ///
+  /// The icon is converted to the platform format here (an `HICON` on Windows, a `GdkPixbuf`
+  /// compatible buffer on Linux), so conversion failures are returned by this function rather
+  /// than by `Window::set_window_icon`. It can be called from any thread.
   pub fn from_rgba(rgba: Vec<u8>, width: u32, height: u32) -> Result<Self, BadIcon> {
     Ok(Icon {
       inner: PlatformIcon::from_rgba(rgba, width, height)?,
     })
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn rgba_icon_accepts_valid_input() {
+    // a 2x2 icon with a fully transparent, a semi-transparent and two opaque pixels
+    let rgba = vec![0, 0, 0, 0, 255, 0, 0, 128, 0, 255, 0, 255, 0, 0, 255, 255];
+    assert!(RgbaIcon::from_rgba(rgba, 2, 2).is_ok());
+  }
+
+  #[test]
+  fn rgba_icon_rejects_bad_input() {
+    assert!(matches!(
+      RgbaIcon::from_rgba(vec![0; 4], 0, 1),
+      Err(BadIcon::DimensionsZero { .. })
+    ));
+    assert!(matches!(
+      RgbaIcon::from_rgba(vec![0; 15], 2, 2),
+      Err(BadIcon::ByteCountNotDivisibleBy4 { .. })
+    ));
+    assert!(matches!(
+      RgbaIcon::from_rgba(vec![0; 12], 2, 2),
+      Err(BadIcon::DimensionsVsPixelCount { .. })
+    ));
+  }
+}
@@ -108,12 +108,32 @@ impl std::fmt::Display for VideoMode {
   }
 }
 
+/// HDR capabilities of a monitor, see [`MonitorHandle::hdr_metadata`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HdrMetadata {
+  /// Whether the monitor can currently display HDR content.
+  pub supports_hdr: bool,
+  /// The maximum luminance of the monitor in nits, if known.
+  pub max_luminance: Option<f32>,
+  /// The minimum luminance of the monitor in nits, if known.
+  pub min_luminance: Option<f32>,
+}
+
 /// Handle to a monitor.
 ///
 /// Allows you to retrieve information about a given monitor and can be used in [`Window`] creation.
 ///
+/// Handles obtained at different times for the same monitor compare equal and hash identically,
+/// so they can be used to match a monitor chosen earlier.
+///
+/// ## Platform-specific
+///
+/// - **Linux Wayland:** Handles of a monitor that was unplugged and plugged in again don't compare
+///   equal.
+///
 /// [`Window`]: crate::window::Window
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct MonitorHandle {
   pub(crate) inner: platform_impl::MonitorHandle,
 }
@@ -147,6 +167,9 @@ impl MonitorHandle {
   /// ## Platform-specific
   ///
   /// - **Android:** Always returns 1.0.
+  /// - **Linux:** GTK 3 only supports integer scale factors. With fractional scaling (e.g. 125%),
+  ///   this is the scale factor rounded up and the compositor downscales the window, so physical
+  ///   sizes are those of the window buffer rather than of the screen.
   #[inline]
   pub fn scale_factor(&self) -> f64 {
     self.inner.scale_factor()
@@ -160,4 +183,19 @@ impl MonitorHandle {
   pub fn video_modes(&self) -> impl Iterator<Item = VideoMode> {
     self.inner.video_modes()
   }
+
+  /// Returns the HDR capabilities of this monitor.
+  ///
+  /// Returns `None` if they can't be queried.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** `supports_hdr` is `true` when HDR is turned on for the monitor in the display settings.
+  /// - **macOS:** `supports_hdr` is `true` when the screen can show extended dynamic range content.
+  ///   Luminance values are not available.
+  /// - **Linux / iOS / Android:** Unsupported, always returns `None`.
+  #[inline]
+  pub fn hdr_metadata(&self) -> Option<HdrMetadata> {
+    self.inner.hdr_metadata()
+  }
 }
@@ -4,7 +4,7 @@
 
 #![cfg(target_os = "macos")]
 
-use std::os::raw::c_void;
+use std::{os::raw::c_void, path::Path};
 
 use crate::{
   dpi::{LogicalSize, Position},
@@ -59,6 +59,12 @@ pub trait WindowExtMacOS {
   /// Get the window's edit state
   fn is_document_edited(&self) -> bool;
 
+  /// Sets the file the window represents, showing its icon next to the title. An empty path
+  /// removes it.
+  ///
+  /// <https://developer.apple.com/documentation/appkit/nswindow/1419192-representedfilename>
+  fn set_represented_filename<P: AsRef<Path>>(&self, path: P);
+
   /// Sets whether the system can automatically organize windows into tabs.
   ///
   /// <https://developer.apple.com/documentation/appkit/nswindow/1646657-allowsautomaticwindowtabbing>
@@ -84,6 +90,23 @@ pub trait WindowExtMacOS {
   ///
   /// <https://developer.apple.com/documentation/appkit/nswindow/1419167-titlebarappearstransparent>
   fn set_titlebar_transparent(&self, transparent: bool);
+
+  /// Sets the subtitle displayed under the window title. An empty string removes it.
+  ///
+  /// This is a no-op before macOS 11.
+  ///
+  /// <https://developer.apple.com/documentation/appkit/nswindow/3608198-subtitle>
+  fn set_subtitle(&self, subtitle: &str);
+
+  /// Shows or hides the close, minimize and zoom buttons of the title bar.
+  ///
+  /// <https://developer.apple.com/documentation/appkit/nswindow/1419491-standardwindowbutton>
+  fn set_window_buttons_visible(&self, visible: bool);
+
+  /// Returns whether the close, minimize and zoom buttons of the title bar are visible.
+  ///
+  /// This is `false` for windows without a title bar.
+  fn window_buttons_visible(&self) -> bool;
 }
 
 impl WindowExtMacOS for Window {
@@ -132,6 +155,11 @@ impl WindowExtMacOS for Window {
     self.window.is_document_edited()
   }
 
+  #[inline]
+  fn set_represented_filename<P: AsRef<Path>>(&self, path: P) {
+    self.window.set_represented_filename(path)
+  }
+
   #[inline]
   fn set_allows_automatic_window_tabbing(&self, enabled: bool) {
     self.window.set_allows_automatic_window_tabbing(enabled)
@@ -161,6 +189,21 @@ impl WindowExtMacOS for Window {
   fn set_titlebar_transparent(&self, transparent: bool) {
     self.window.set_titlebar_transparent(transparent);
   }
+
+  #[inline]
+  fn set_subtitle(&self, subtitle: &str) {
+    self.window.set_subtitle(subtitle);
+  }
+
+  #[inline]
+  fn set_window_buttons_visible(&self, visible: bool) {
+    self.window.set_window_buttons_visible(visible);
+  }
+
+  #[inline]
+  fn window_buttons_visible(&self) -> bool {
+    self.window.window_buttons_visible()
+  }
 }
 
 /// Corresponds to `NSApplicationActivationPolicy`.
@@ -191,6 +234,24 @@ impl From<ActivationPolicy> for NSApplicationActivationPolicy {
   }
 }
 
+/// Options for the standard about panel shown by
+/// [`EventLoopWindowTargetExtMacOS::show_about_panel`].
+///
+/// Fields left to `None` fall back to the values in the app's `Info.plist`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AboutPanelOptions {
+  /// The application name, `CFBundleName` by default.
+  pub name: Option<String>,
+  /// The version shown next to the name, `CFBundleShortVersionString` by default.
+  pub version: Option<String>,
+  /// The build version shown in parentheses after `version`, `CFBundleVersion` by default.
+  pub build: Option<String>,
+  /// The copyright notice, `NSHumanReadableCopyright` by default.
+  pub copyright: Option<String>,
+  /// The text shown in the scrollable credits area, the app's `Credits` resource file by default.
+  pub credits: Option<String>,
+}
+
 /// Additional methods on `WindowBuilder` that are specific to MacOS.
 ///
 /// **Note:** Properties dealing with the titlebar will be overwritten by the `with_decorations` method
@@ -229,6 +290,18 @@ pub trait WindowBuilderExtMacOS {
   ///
   /// [tabbing identifier]: <https://developer.apple.com/documentation/appkit/nswindow/1644704-tabbingidentifier>
   fn with_tabbing_identifier(self, identifier: &str) -> WindowBuilder;
+  /// Sets whether showing the window on creation activates the application.
+  ///
+  /// - `true`: the window is shown and the application is activated, unless its
+  ///   activation policy is [`ActivationPolicy::Prohibited`].
+  /// - `false`: the window is shown without activating the application. If the
+  ///   window is also [focused](WindowBuilder::with_focused), it becomes the key
+  ///   window of the application (`orderFront:` + `makeKeyWindow`) rather than
+  ///   going through `makeKeyAndOrderFront:`, which is the usual pattern for
+  ///   auxiliary panels.
+  ///
+  /// When not set, the window is shown according to its `focused` attribute alone.
+  fn with_active(self, active: bool) -> WindowBuilder;
 }
 
 impl WindowBuilderExtMacOS for WindowBuilder {
@@ -315,6 +388,12 @@ impl WindowBuilderExtMacOS for WindowBuilder {
       .replace(tabbing_identifier.into());
     self
   }
+
+  #[inline]
+  fn with_active(mut self, active: bool) -> WindowBuilder {
+    self.platform_specific.active = Some(active);
+    self
+  }
 }
 
 pub trait EventLoopExtMacOS {
@@ -388,6 +467,8 @@ pub trait EventLoopWindowTargetExtMacOS {
   /// To set the activation policy before the app starts running, see
   /// [`EventLoopExtMacOS::set_activation_policy`](crate::platform::macos::EventLoopExtMacOS::set_activation_policy).
   fn set_activation_policy_at_runtime(&self, activation_policy: ActivationPolicy);
+  /// Shows the standard about panel, like the "About" item of the application menu does.
+  fn show_about_panel(&self, options: AboutPanelOptions);
 }
 
 impl<T> EventLoopWindowTargetExtMacOS for EventLoopWindowTarget<T> {
@@ -415,4 +496,44 @@ impl<T> EventLoopWindowTargetExtMacOS for EventLoopWindowTarget<T> {
     let ns_activation_policy: NSApplicationActivationPolicy = activation_policy.into();
     unsafe { msg_send![app, setActivationPolicy: ns_activation_policy] }
   }
+
+  fn show_about_panel(&self, options: AboutPanelOptions) {
+    use cocoa::{
+      base::{id, nil},
+      foundation::NSString,
+    };
+
+    let cls = objc::runtime::Class::get("NSApplication").unwrap();
+    let app: id = unsafe { msg_send![cls, sharedApplication] };
+    unsafe {
+      let dict: id = msg_send![class!(NSMutableDictionary), new];
+      let entries = [
+        ("ApplicationName", options.name),
+        ("ApplicationVersion", options.version),
+        ("Version", options.build),
+        ("Copyright", options.copyright),
+      ];
+      for (key, value) in entries {
+        if let Some(value) = value {
+          let key = NSString::alloc(nil).init_str(key);
+          let value = NSString::alloc(nil).init_str(&value);
+          let () = msg_send![dict, setObject: value forKey: key];
+          let () = msg_send![value, release];
+          let () = msg_send![key, release];
+        }
+      }
+      if let Some(credits) = options.credits {
+        let key = NSString::alloc(nil).init_str("Credits");
+        let string = NSString::alloc(nil).init_str(&credits);
+        let credits: id = msg_send![class!(NSAttributedString), alloc];
+        let credits: id = msg_send![credits, initWithString: string];
+        let () = msg_send![dict, setObject: credits forKey: key];
+        let () = msg_send![credits, release];
+        let () = msg_send![string, release];
+        let () = msg_send![key, release];
+      }
+      let () = msg_send![app, orderFrontStandardAboutPanelWithOptions: dict];
+      let () = msg_send![dict, release];
+    }
+  }
 }
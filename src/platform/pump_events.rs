@@ -0,0 +1,77 @@
+// Copyright 2014-2021 The winit contributors
+// Copyright 2021-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(all(
+  feature = "test-util",
+  any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  )
+))]
+
+use std::time::Duration;
+
+use crate::{
+  event::Event,
+  event_loop::{ControlFlow, EventLoop, EventLoopWindowTarget},
+};
+
+/// The status returned by [`EventLoopExtPumpEvents::pump_events`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PumpStatus {
+  /// The event loop is still running and `pump_events` can be called again.
+  Continue,
+  /// The event loop was exited with the given code and `Event::LoopDestroyed` has been sent.
+  Exit(i32),
+}
+
+/// Additional methods on `EventLoop` to drive it step by step, mainly for tests.
+///
+/// This is only available with the `test-util` feature.
+pub trait EventLoopExtPumpEvents {
+  /// A type provided by the user that can be passed through `Event::UserEvent`.
+  type UserEvent;
+
+  /// Runs a single iteration of the event loop and returns control to the caller.
+  ///
+  /// If no events are available, this waits for up to `timeout` for some to arrive, or
+  /// indefinitely if `timeout` is `None`. Pass `Some(Duration::ZERO)` to never wait. Every
+  /// event received is then delivered to `event_handler` followed by `Event::MainEventsCleared`,
+  /// the pending `Event::RedrawRequested`s and `Event::RedrawEventsCleared`, like in an
+  /// iteration of `run`. The first call delivers `StartCause::Init`.
+  ///
+  /// Setting `control_flow` to `ControlFlow::ExitWithCode` sends `Event::LoopDestroyed` and makes
+  /// this return [`PumpStatus::Exit`]. The event loop must not be pumped again after that.
+  /// Other control flows only affect how the next iteration is started.
+  ///
+  /// # Caveats
+  /// Like [`run_return`](crate::platform::run_return::EventLoopExtRunReturn::run_return), this will
+  /// not return on Windows or macOS while a window is being resized or moved.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS:** `Event::NewEvents` is only sent when the run loop actually had to wait.
+  fn pump_events<F>(&mut self, timeout: Option<Duration>, event_handler: F) -> PumpStatus
+  where
+    F: FnMut(Event<'_, Self::UserEvent>, &EventLoopWindowTarget<Self::UserEvent>, &mut ControlFlow);
+}
+
+impl<T> EventLoopExtPumpEvents for EventLoop<T> {
+  type UserEvent = T;
+
+  fn pump_events<F>(&mut self, timeout: Option<Duration>, event_handler: F) -> PumpStatus
+  where
+    F: FnMut(Event<'_, Self::UserEvent>, &EventLoopWindowTarget<Self::UserEvent>, &mut ControlFlow),
+  {
+    self.event_loop.pump_events(
+      timeout,
+      crate::event_loop::wrap_event_handler(event_handler),
+    )
+  }
+}
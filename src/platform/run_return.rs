@@ -45,6 +45,8 @@ impl<T> EventLoopExtRunReturn for EventLoop<T> {
   where
     F: FnMut(Event<'_, Self::UserEvent>, &EventLoopWindowTarget<Self::UserEvent>, &mut ControlFlow),
   {
-    self.event_loop.run_return(event_handler)
+    self
+      .event_loop
+      .run_return(crate::event_loop::wrap_event_handler(event_handler))
   }
 }
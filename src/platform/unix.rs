@@ -69,15 +69,26 @@ pub trait WindowExtUnix {
     window: gtk::ApplicationWindow,
   ) -> Result<Window, OsError>;
 
-  /// Returns the `gtk::ApplicatonWindow` from gtk crate that is used by this window.
+  /// Returns the `gtk::ApplicationWindow` from gtk crate that is used by this window.
+  ///
+  /// GTK is not thread-safe: the returned window must only be used on the thread running the
+  /// event loop. It lives as long as this [`Window`]; don't destroy it yourself, drop the
+  /// [`Window`] instead. Changes made directly to it, such as its size or decorations, may not
+  /// be reflected by the state tao tracks for the window.
   fn gtk_window(&self) -> &gtk::ApplicationWindow;
 
   /// Returns the vertical `gtk::Box` that is added by default as the sole child of this window.
   /// Returns `None` if the default vertical `gtk::Box` creation was disabled by [`WindowBuilderExtUnix::with_default_vbox`].
+  ///
+  /// The same thread and lifetime caveats as [`WindowExtUnix::gtk_window`] apply.
   fn default_vbox(&self) -> Option<&gtk::Box>;
 
   /// Whether to show the window icon in the taskbar or not.
   fn set_skip_taskbar(&self, skip: bool) -> Result<(), ExternalError>;
+
+  /// Shows the window like [`Window::set_visible`] does but asks the window manager
+  /// not to focus it.
+  fn show_without_focus(&self);
 }
 
 impl WindowExtUnix for Window {
@@ -93,12 +104,19 @@ impl WindowExtUnix for Window {
     self.window.set_skip_taskbar(skip)
   }
 
+  fn show_without_focus(&self) {
+    self.window.show_without_focus()
+  }
+
   fn new_from_gtk_window<T: 'static>(
     event_loop_window_target: &EventLoopWindowTarget<T>,
     window: gtk::ApplicationWindow,
   ) -> Result<Window, OsError> {
     let window = UnixWindow::new_from_gtk_window(&event_loop_window_target.p, window)?;
-    Ok(Window { window: window })
+    Ok(Window {
+      window,
+      resize_debounces: event_loop_window_target.resize_debouncer.debounces.clone(),
+    })
   }
 }
 
@@ -10,16 +10,30 @@ use crate::{
   dpi::PhysicalSize,
   error::ExternalError,
   event::DeviceId,
-  event_loop::EventLoopBuilder,
+  event_loop::{EventLoopBuilder, EventLoopWindowTarget},
   monitor::MonitorHandle,
-  platform_impl::{Parent, WinIcon},
-  window::{BadIcon, Icon, Theme, Window, WindowBuilder},
+  platform_impl::{FrameColor, Parent, WinIcon},
+  window::{BadIcon, Icon, Theme, Window, WindowBuilder, RGBA},
 };
 use windows::Win32::UI::Input::KeyboardAndMouse::*;
 
 pub type HWND = isize;
 pub type HMENU = isize;
 
+/// How the corners of a window are rounded on Windows 11.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CornerPreference {
+  /// Let the system decide, which rounds the corners of most windows.
+  #[default]
+  Default,
+  /// Never round the corners.
+  DoNotRound,
+  /// Round the corners if appropriate.
+  Round,
+  /// Round the corners with a small radius if appropriate.
+  RoundSmall,
+}
+
 /// Additional methods on `EventLoop` that are specific to Windows.
 pub trait EventLoopBuilderExtWindows {
   /// Whether to allow the event loop to be created off of the main thread.
@@ -132,6 +146,90 @@ impl<T> EventLoopBuilderExtWindows for EventLoopBuilder<T> {
   }
 }
 
+/// Additional methods on `EventLoopWindowTarget` that are specific to Windows.
+pub trait EventLoopWindowTargetExtWindows<T: 'static> {
+  /// Registers a handler for `WM_COPYDATA` messages sent to any window of this event loop.
+  ///
+  /// The handler receives the `dwData` value and the copied bytes of the `COPYDATASTRUCT`.
+  /// If it returns `Some(event)`, the event is dispatched as [`Event::UserEvent`].
+  ///
+  /// This is useful for single-instance apps: a newly launched instance can find a window of
+  /// the running instance and forward its command line or deep-link URL with
+  /// `SendMessageW(hwnd, WM_COPYDATA, ..)`. Detecting the running instance is left to the app.
+  ///
+  /// Replaces any previously registered handler.
+  ///
+  /// # Example
+  ///
+  /// ```no_run
+  /// use tao::{event::Event, event_loop::EventLoopBuilder};
+  /// #[cfg(target_os = "windows")]
+  /// use tao::platform::windows::EventLoopWindowTargetExtWindows;
+  ///
+  /// let event_loop = EventLoopBuilder::<String>::with_user_event().build();
+  /// #[cfg(target_os = "windows")]
+  /// event_loop.set_copy_data_handler(|_data, payload| {
+  ///   String::from_utf8(payload.to_vec()).ok()
+  /// });
+  ///
+  /// event_loop.run(|event, _, _| {
+  ///   if let Event::UserEvent(args) = event {
+  ///     println!("forwarded from another instance: {args}");
+  ///   }
+  /// });
+  /// ```
+  ///
+  /// [`Event::UserEvent`]: crate::event::Event::UserEvent
+  fn set_copy_data_handler<F>(&self, handler: F)
+  where
+    F: FnMut(usize, &[u8]) -> Option<T> + 'static;
+
+  /// Removes the handler registered with [`EventLoopWindowTargetExtWindows::set_copy_data_handler`].
+  fn remove_copy_data_handler(&self);
+
+  /// Returns the number of lines to scroll per notch of the mouse wheel, as set in the system
+  /// settings. Multiply the deltas of [`MouseScrollDelta::LineDelta`] with it to scroll like
+  /// native apps do.
+  ///
+  /// Returns `u32::MAX` (`WHEEL_PAGESCROLL`) when the wheel is set to scroll a page at a time.
+  /// The setting is read on every call, so it is always up to date.
+  ///
+  /// [`MouseScrollDelta::LineDelta`]: crate::event::MouseScrollDelta::LineDelta
+  fn wheel_scroll_lines(&self) -> u32;
+
+  /// Returns the number of characters to scroll per notch of a horizontal mouse wheel, as set in
+  /// the system settings. See [`EventLoopWindowTargetExtWindows::wheel_scroll_lines`].
+  fn wheel_scroll_chars(&self) -> u32;
+}
+
+impl<T: 'static> EventLoopWindowTargetExtWindows<T> for EventLoopWindowTarget<T> {
+  #[inline]
+  fn set_copy_data_handler<F>(&self, handler: F)
+  where
+    F: FnMut(usize, &[u8]) -> Option<T> + 'static,
+  {
+    self
+      .p
+      .runner_shared
+      .set_copy_data_handler(Some(Box::new(handler)));
+  }
+
+  #[inline]
+  fn remove_copy_data_handler(&self) {
+    self.p.runner_shared.set_copy_data_handler(None);
+  }
+
+  #[inline]
+  fn wheel_scroll_lines(&self) -> u32 {
+    self.p.wheel_scroll_lines()
+  }
+
+  #[inline]
+  fn wheel_scroll_chars(&self) -> u32 {
+    self.p.wheel_scroll_chars()
+  }
+}
+
 /// Additional methods on `Window` that are specific to Windows.
 pub trait WindowExtWindows {
   /// Returns the HINSTANCE of the window
@@ -172,9 +270,28 @@ pub trait WindowExtWindows {
   /// Starts the resizing drag from given edge
   fn begin_resize_drag(&self, edge: isize, button: u32, x: i32, y: i32);
 
+  /// Starts the keyboard driven move loop of the system, like the "Move" item of the window menu
+  /// (Alt+Space).
+  ///
+  /// This also works for undecorated windows, which is useful to keep them movable with the
+  /// keyboard when they have a custom title bar.
+  fn begin_system_move(&self);
+
+  /// Starts the keyboard driven resize loop of the system, like the "Size" item of the window
+  /// menu (Alt+Space).
+  ///
+  /// This also works for undecorated windows. It does nothing if the window isn't resizable.
+  fn begin_system_resize(&self);
+
   /// Whether to show the window icon in the taskbar or not.
   fn set_skip_taskbar(&self, skip: bool) -> Result<(), ExternalError>;
 
+  /// Enables or disables drag and drop support after the window was created, for example to
+  /// reject drops during a modal operation.
+  ///
+  /// Enabling it has the same COM requirements as [`WindowBuilderExtWindows::with_drag_and_drop`].
+  fn set_enable_drag_drop(&self, enabled: bool);
+
   /// Shows or hides the background drop shadow for undecorated windows.
   ///
   /// Enabling the shadow causes a thin 1px line to appear on the top of the window.
@@ -184,6 +301,68 @@ pub trait WindowExtWindows {
   ///
   /// Enabling this mainly flips the orientation of menus and title bar buttons
   fn set_rtl(&self, rtl: bool);
+
+  /// Blurs what is behind the window, on Windows 10 and later.
+  ///
+  /// The window must be [transparent](crate::window::WindowBuilder::with_transparent) for the
+  /// blur to be visible.
+  ///
+  /// This relies on the undocumented `SetWindowCompositionAttribute` API, and does nothing on
+  /// systems where it isn't available.
+  fn set_blur(&self, blur: bool);
+
+  /// Delivers Alt+F4 as a regular [`WindowEvent::KeyboardInput`] instead of letting the system
+  /// close the window, so the application can decide what to do with it.
+  ///
+  /// Defaults to `false`, in which case Alt+F4 results in a [`WindowEvent::CloseRequested`].
+  ///
+  /// [`WindowEvent::KeyboardInput`]: crate::event::WindowEvent::KeyboardInput
+  /// [`WindowEvent::CloseRequested`]: crate::event::WindowEvent::CloseRequested
+  fn set_intercept_alt_f4(&self, intercept: bool);
+
+  /// Sets how the corners of the window are rounded.
+  ///
+  /// This does nothing on versions older than Windows 11.
+  fn set_corner_preference(&self, preference: CornerPreference);
+
+  /// Sets the color of the window border, or reverts to the default one with `None`.
+  ///
+  /// The alpha component is ignored. This does nothing on versions older than Windows 11.
+  fn set_border_color(&self, color: Option<RGBA>);
+
+  /// Sets the background color of the title bar, or reverts to the default one with `None`.
+  ///
+  /// The alpha component is ignored. This does nothing on versions older than Windows 11.
+  fn set_title_bar_color(&self, color: Option<RGBA>);
+
+  /// Sets the color of the title bar text, or reverts to the default one with `None`.
+  ///
+  /// The alpha component is ignored. This does nothing on versions older than Windows 11.
+  fn set_title_text_color(&self, color: Option<RGBA>);
+
+  /// Overrides the scale factor derived from the monitor DPI, or restores it when `None`.
+  ///
+  /// The window is resized to keep its logical size and a
+  /// [`WindowEvent::ScaleFactorChanged`] is emitted, just like on a real DPI change. While an
+  /// override is set, DPI changes from the system are ignored.
+  ///
+  /// This only affects tao's conversions between logical and physical coordinates, not the
+  /// scaling applied by the OS compositor. It is mainly meant for tests and for applications
+  /// that want to pin their UI scale. Invalid scale factors (non-positive or not finite) are
+  /// ignored.
+  ///
+  /// [`WindowEvent::ScaleFactorChanged`]: crate::event::WindowEvent::ScaleFactorChanged
+  fn force_scale_factor(&self, scale_factor: Option<f64>);
+
+  /// Sets the reason shown to the user for keeping the session from ending, e.g. "Saving
+  /// documents", or removes it with `None`.
+  ///
+  /// While a reason is set on a window, logging out or shutting down the system is cancelled
+  /// and [`Event::SessionEnd`] isn't emitted with `ending: true`. The user can still choose to
+  /// end the session anyway. The reason should only be set while there is unsaved data.
+  ///
+  /// [`Event::SessionEnd`]: crate::event::Event::SessionEnd
+  fn set_shutdown_block_reason(&self, reason: Option<&str>);
 }
 
 impl WindowExtWindows for Window {
@@ -224,11 +403,26 @@ impl WindowExtWindows for Window {
     self.window.begin_resize_drag(edge, button, x, y)
   }
 
+  #[inline]
+  fn begin_system_move(&self) {
+    self.window.begin_system_move()
+  }
+
+  #[inline]
+  fn begin_system_resize(&self) {
+    self.window.begin_system_resize()
+  }
+
   #[inline]
   fn set_skip_taskbar(&self, skip: bool) -> Result<(), ExternalError> {
     self.window.set_skip_taskbar(skip)
   }
 
+  #[inline]
+  fn set_enable_drag_drop(&self, enabled: bool) {
+    self.window.set_enable_drag_drop(enabled)
+  }
+
   #[inline]
   fn set_undecorated_shadow(&self, shadow: bool) {
     self.window.set_undecorated_shadow(shadow)
@@ -238,6 +432,46 @@ impl WindowExtWindows for Window {
   fn set_rtl(&self, rtl: bool) {
     self.window.set_rtl(rtl)
   }
+
+  #[inline]
+  fn set_blur(&self, blur: bool) {
+    self.window.set_blur(blur)
+  }
+
+  #[inline]
+  fn set_intercept_alt_f4(&self, intercept: bool) {
+    self.window.set_intercept_alt_f4(intercept)
+  }
+
+  #[inline]
+  fn set_corner_preference(&self, preference: CornerPreference) {
+    self.window.set_corner_preference(preference)
+  }
+
+  #[inline]
+  fn set_border_color(&self, color: Option<RGBA>) {
+    self.window.set_frame_color(FrameColor::Border, color)
+  }
+
+  #[inline]
+  fn set_title_bar_color(&self, color: Option<RGBA>) {
+    self.window.set_frame_color(FrameColor::TitleBar, color)
+  }
+
+  #[inline]
+  fn set_title_text_color(&self, color: Option<RGBA>) {
+    self.window.set_frame_color(FrameColor::TitleText, color)
+  }
+
+  #[inline]
+  fn force_scale_factor(&self, scale_factor: Option<f64>) {
+    self.window.force_scale_factor(scale_factor)
+  }
+
+  #[inline]
+  fn set_shutdown_block_reason(&self, reason: Option<&str>) {
+    self.window.set_shutdown_block_reason(reason)
+  }
 }
 
 /// Additional methods on `WindowBuilder` that are specific to Windows.
@@ -268,6 +502,10 @@ pub trait WindowBuilderExtWindows {
   /// The menu must have been manually created beforehand with [`windows::Win32::UI::WindowsAndMessaging::CreateMenu`]
   /// or similar.
   ///
+  /// The menu bar is removed while the window is undecorated, see
+  /// [`WindowBuilder::with_decorations`] and [`Window::set_decorations`], and comes back with the
+  /// decorations.
+  ///
   /// Note: Dark mode cannot be supported for win32 menus, it's simply not possible to change how the menus look.
   /// If you use this, it is recommended that you combine it with `with_theme(Some(Theme::Light))` to avoid a jarring effect.
   fn with_menu(self, menu: HMENU) -> WindowBuilder;
@@ -60,6 +60,8 @@ pub struct EventLoop<T: 'static> {
   start_cause: event::StartCause,
   looper: ThreadLooper,
   running: bool,
+  /// The size last reported with `WindowEvent::Resized`.
+  last_size: Option<PhysicalSize<u32>>,
 }
 
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -84,6 +86,11 @@ impl<T: 'static> EventLoop<T> {
         p: EventLoopWindowTarget {
           _marker: std::marker::PhantomData,
         },
+        control_flow: Default::default(),
+        busy_cursor_depth: Default::default(),
+        resize_debouncer: Default::default(),
+        #[cfg(feature = "trace")]
+        stats: Default::default(),
         _marker: std::marker::PhantomData,
       },
       sender_to_clone: sender,
@@ -92,6 +99,7 @@ impl<T: 'static> EventLoop<T> {
       start_cause: event::StartCause::Init,
       looper: ThreadLooper::for_thread().unwrap(),
       running: false,
+      last_size: None,
     }
   }
 
@@ -120,6 +128,7 @@ impl<T: 'static> EventLoop<T> {
 
       let mut redraw = false;
       let mut resized = false;
+      let mut config_changed = false;
 
       match self.first_event.take() {
         Some(EventSource::Callback) => match ndk_glue::poll_events().unwrap() {
@@ -144,6 +153,9 @@ impl<T: 'static> EventLoop<T> {
           Event::Stop => self.running = false,
           Event::Start => self.running = true,
           Event::ConfigChanged => {
+            // Entering split-screen or resizing a freeform window changes the configuration
+            // without necessarily resizing the native window.
+            config_changed = true;
             // #[allow(deprecated)] // TODO: use ndk-context instead
             // let am = ndk_glue::native_activity().asset_manager();
             // let config = Configuration::from_asset_manager(&am);
@@ -302,13 +314,16 @@ impl<T: 'static> EventLoop<T> {
         event::Event::MainEventsCleared
       );
 
-      if resized && self.running {
+      if (resized || config_changed) && self.running {
         let size = MonitorHandle.size();
-        let event = event::Event::WindowEvent {
-          window_id: window::WindowId(WindowId),
-          event: event::WindowEvent::Resized(size),
-        };
-        call_event_handler!(event_handler, self.window_target(), control_flow, event);
+        if resized || self.last_size != Some(size) {
+          self.last_size = Some(size);
+          let event = event::Event::WindowEvent {
+            window_id: window::WindowId(WindowId),
+            event: event::WindowEvent::Resized(size),
+          };
+          call_event_handler!(event_handler, self.window_target(), control_flow, event);
+        }
       }
 
       if redraw && self.running {
@@ -423,6 +438,10 @@ impl<T: 'static> EventLoopWindowTarget<T> {
     })
   }
 
+  pub fn current_monitor(&self) -> Option<monitor::MonitorHandle> {
+    self.primary_monitor()
+  }
+
   #[inline]
   pub fn monitor_from_point(&self, _x: f64, _y: f64) -> Option<MonitorHandle> {
     warn!("`Window::monitor_from_point` is ignored on Android");
@@ -548,9 +567,15 @@ impl Window {
     MonitorHandle.size()
   }
 
-  pub fn set_min_inner_size(&self, _: Option<Size>) {}
-  pub fn set_max_inner_size(&self, _: Option<Size>) {}
-  pub fn set_inner_size_constraints(&self, _: WindowSizeConstraints) {}
+  pub fn set_min_inner_size(&self, _: Option<Size>) {
+    warn!("`Window::set_min_inner_size` is ignored on Android")
+  }
+  pub fn set_max_inner_size(&self, _: Option<Size>) {
+    warn!("`Window::set_max_inner_size` is ignored on Android")
+  }
+  pub fn set_inner_size_constraints(&self, _: WindowSizeConstraints) {
+    warn!("`Window::set_inner_size_constraints` is ignored on Android")
+  }
 
   pub fn set_title(&self, _title: &str) {}
   pub fn title(&self) -> String {
@@ -564,11 +589,23 @@ impl Window {
     warn!("set_focus not yet implemented on Android");
   }
 
+  pub fn activate(&self) {
+    warn!("`Window::activate` is ignored on Android");
+  }
+
   pub fn is_focused(&self) -> bool {
     log::warn!("`Window::is_focused` is ignored on Android");
     false
   }
 
+  pub fn owner(&self) -> Option<WindowId> {
+    None
+  }
+
+  pub fn owned_windows(&self) -> Vec<WindowId> {
+    Vec::new()
+  }
+
   pub fn is_always_on_top(&self) -> bool {
     log::warn!("`Window::is_always_on_top` is ignored on Android");
     false
@@ -648,8 +685,16 @@ impl Window {
 
   pub fn set_window_icon(&self, _window_icon: Option<crate::icon::Icon>) {}
 
+  pub fn window_icon(&self) -> Option<crate::icon::Icon> {
+    None
+  }
+
+  pub fn set_drag_region(&self, _region: Option<(Position, Size)>) {}
+
   pub fn set_ime_position(&self, _position: Position) {}
 
+  pub fn set_ime_allowed(&self, _allowed: bool) {}
+
   pub fn request_user_attention(&self, _request_type: Option<window::UserAttentionType>) {}
 
   pub fn set_cursor_icon(&self, _: window::CursorIcon) {}
@@ -660,6 +705,12 @@ impl Window {
     ))
   }
 
+  pub fn set_cursor_position_screen(&self, _: Position) -> Result<(), error::ExternalError> {
+    Err(error::ExternalError::NotSupported(
+      error::NotSupportedError::new(),
+    ))
+  }
+
   pub fn set_cursor_grab(&self, _: bool) -> Result<(), error::ExternalError> {
     Err(error::ExternalError::NotSupported(
       error::NotSupportedError::new(),
@@ -841,6 +892,10 @@ impl MonitorHandle {
     });
     v.into_iter()
   }
+
+  pub fn hdr_metadata(&self) -> Option<monitor::HdrMetadata> {
+    None
+  }
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
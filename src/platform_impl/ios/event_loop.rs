@@ -76,6 +76,10 @@ impl<T: 'static> EventLoopWindowTarget<T> {
     Some(RootMonitorHandle { inner: monitor })
   }
 
+  pub fn current_monitor(&self) -> Option<RootMonitorHandle> {
+    self.primary_monitor()
+  }
+
   #[cfg(feature = "rwh_05")]
   #[inline]
   pub fn raw_display_handle_rwh_05(&self) -> rwh_05::RawDisplayHandle {
@@ -128,6 +132,11 @@ impl<T: 'static> EventLoop<T> {
           receiver,
           sender_to_clone,
         },
+        control_flow: Default::default(),
+        busy_cursor_depth: Default::default(),
+        resize_debouncer: Default::default(),
+        #[cfg(feature = "trace")]
+        stats: Default::default(),
         _marker: PhantomData,
       },
     }
@@ -10,7 +10,7 @@ use std::{
 
 use crate::{
   dpi::{PhysicalPosition, PhysicalSize},
-  monitor::{MonitorHandle as RootMonitorHandle, VideoMode as RootVideoMode},
+  monitor::{HdrMetadata, MonitorHandle as RootMonitorHandle, VideoMode as RootVideoMode},
   platform_impl::platform::{
     app_state,
     ffi::{id, nil, CGFloat, CGRect, CGSize, NSInteger, NSUInteger},
@@ -254,6 +254,10 @@ impl Inner {
 
     modes.into_iter()
   }
+
+  pub fn hdr_metadata(&self) -> Option<HdrMetadata> {
+    None
+  }
 }
 
 // MonitorHandleExtIOS
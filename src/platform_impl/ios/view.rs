@@ -530,7 +530,7 @@ pub unsafe fn create_window(
       let () = msg_send![uiscreen, setCurrentMode: video_mode.video_mode.screen_mode.0];
       msg_send![window, setScreen:video_mode.monitor().ui_screen()]
     }
-    Some(Fullscreen::Borderless(ref monitor)) => {
+    Some(Fullscreen::Borderless(ref monitor) | Fullscreen::BorderlessWorkArea(ref monitor)) => {
       let uiscreen: id = match &monitor {
         Some(monitor) => monitor.ui_screen() as id,
         None => {
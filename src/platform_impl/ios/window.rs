@@ -20,8 +20,8 @@ use crate::{
     app_state,
     event_loop::{self, EventProxy, EventWrapper},
     ffi::{
-      id, CGFloat, CGPoint, CGRect, CGSize, UIEdgeInsets, UIInterfaceOrientationMask, UIRectEdge,
-      UIScreenOverscanCompensation,
+      id, nil, CGFloat, CGPoint, CGRect, CGSize, UIEdgeInsets, UIInterfaceOrientationMask,
+      UIRectEdge, UIScreenOverscanCompensation,
     },
     monitor, view, EventLoopWindowTarget, MonitorHandle,
   },
@@ -72,11 +72,23 @@ impl Inner {
     warn!("set_focus not yet implemented on iOS");
   }
 
+  pub fn activate(&self) {
+    warn!("`Window::activate` is ignored on iOS");
+  }
+
   pub fn is_focused(&self) -> bool {
     warn!("`Window::is_focused` is ignored on iOS");
     false
   }
 
+  pub fn owner(&self) -> Option<WindowId> {
+    None
+  }
+
+  pub fn owned_windows(&self) -> Vec<WindowId> {
+    Vec::new()
+  }
+
   pub fn is_always_on_top(&self) -> bool {
     log::warn!("`Window::is_always_on_top` is ignored on iOS");
     false
@@ -210,6 +222,10 @@ impl Inner {
     Err(ExternalError::NotSupported(NotSupportedError::new()))
   }
 
+  pub fn set_cursor_position_screen(&self, _position: Position) -> Result<(), ExternalError> {
+    Err(ExternalError::NotSupported(NotSupportedError::new()))
+  }
+
   pub fn set_cursor_grab(&self, _grab: bool) -> Result<(), ExternalError> {
     Err(ExternalError::NotSupported(NotSupportedError::new()))
   }
@@ -291,9 +307,10 @@ impl Inner {
           let () = msg_send![uiscreen, setCurrentMode: video_mode.video_mode.screen_mode.0];
           uiscreen
         }
-        Some(Fullscreen::Borderless(monitor)) => monitor
-          .unwrap_or_else(|| self.current_monitor_inner())
-          .ui_screen() as id,
+        Some(Fullscreen::Borderless(monitor) | Fullscreen::BorderlessWorkArea(monitor)) => {
+          let monitor = monitor.unwrap_or_else(|| self.current_monitor_inner());
+          monitor.ui_screen() as id
+        }
         None => {
           warn!("`Window::set_fullscreen(None)` ignored on iOS");
           return;
@@ -355,28 +372,47 @@ impl Inner {
     warn!("`Window::set_window_icon` is ignored on iOS")
   }
 
+  pub fn window_icon(&self) -> Option<Icon> {
+    None
+  }
+
+  pub fn set_drag_region(&self, _region: Option<(Position, Size)>) {
+    warn!("`Window::set_drag_region` is ignored on iOS")
+  }
+
   pub fn set_ime_position(&self, _position: Position) {
     warn!("`Window::set_ime_position` is ignored on iOS")
   }
 
+  pub fn set_ime_allowed(&self, _allowed: bool) {
+    warn!("`Window::set_ime_allowed` is ignored on iOS")
+  }
+
   pub fn request_user_attention(&self, _request_type: Option<UserAttentionType>) {
     warn!("`Window::request_user_attention` is ignored on iOS")
   }
 
   // Allow directly accessing the current monitor internally without unwrapping.
   fn current_monitor_inner(&self) -> RootMonitorHandle {
+    self.current_monitor().unwrap_or_else(|| RootMonitorHandle {
+      inner: unsafe { monitor::main_uiscreen() },
+    })
+  }
+
+  pub fn current_monitor(&self) -> Option<RootMonitorHandle> {
     unsafe {
+      // `screen` is nil while the window is not attached to any screen,
+      // e.g. right after its external display got disconnected
       let uiscreen: id = msg_send![self.window, screen];
-      RootMonitorHandle {
-        inner: MonitorHandle::retained_new(uiscreen),
+      if uiscreen == nil {
+        return None;
       }
+      Some(RootMonitorHandle {
+        inner: MonitorHandle::retained_new(uiscreen),
+      })
     }
   }
 
-  pub fn current_monitor(&self) -> Option<RootMonitorHandle> {
-    Some(self.current_monitor_inner())
-  }
-
   pub fn available_monitors(&self) -> VecDeque<MonitorHandle> {
     unsafe { monitor::uiscreens() }
   }
@@ -491,8 +527,13 @@ impl Window {
         Some(Fullscreen::Exclusive(ref video_mode)) => {
           video_mode.video_mode.monitor.ui_screen() as id
         }
-        Some(Fullscreen::Borderless(Some(ref monitor))) => monitor.inner.ui_screen(),
-        Some(Fullscreen::Borderless(None)) | None => monitor::main_uiscreen().ui_screen() as id,
+        Some(
+          Fullscreen::Borderless(Some(ref monitor))
+          | Fullscreen::BorderlessWorkArea(Some(ref monitor)),
+        ) => monitor.inner.ui_screen(),
+        Some(Fullscreen::Borderless(None) | Fullscreen::BorderlessWorkArea(None)) | None => {
+          monitor::main_uiscreen().ui_screen() as id
+        }
       };
 
       let screen_bounds: CGRect = msg_send![screen, bounds];
@@ -3,8 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
-  cell::RefCell,
-  collections::{HashSet, VecDeque},
+  cell::{Cell, RefCell},
+  collections::{HashMap, HashSet, VecDeque},
   error::Error,
   process,
   rc::Rc,
@@ -12,6 +12,12 @@ use std::{
   time::Instant,
 };
 
+#[cfg(feature = "test-util")]
+use std::time::Duration;
+
+#[cfg(feature = "test-util")]
+use crate::platform::pump_events::PumpStatus;
+
 use cairo::{RectangleInt, Region};
 use crossbeam_channel::SendError;
 use gdk::{Cursor, CursorType, EventKey, EventMask, ScrollDirection, WindowEdge, WindowState};
@@ -25,7 +31,7 @@ use gtk::{
 };
 
 use crate::{
-  dpi::{LogicalPosition, LogicalSize, PhysicalPosition},
+  dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize},
   error::ExternalError,
   event::{
     ElementState, Event, MouseButton, MouseScrollDelta, StartCause, TouchPhase, WindowEvent,
@@ -35,7 +41,8 @@ use crate::{
   monitor::MonitorHandle as RootMonitorHandle,
   platform_impl::platform::{device, DEVICE_ID},
   window::{
-    CursorIcon, Fullscreen, ProgressBarState, ResizeDirection, Theme, WindowId as RootWindowId,
+    CursorIcon, Fullscreen, HitTestHandler, HitTestResult, ProgressBarState, ResizeDirection,
+    Theme, WindowId as RootWindowId, WindowMode,
   },
 };
 
@@ -43,7 +50,7 @@ use super::{
   keyboard,
   monitor::{self, MonitorHandle},
   taskbar, util,
-  window::{WindowId, WindowRequest},
+  window::{apply_theme, connect_theme_changed, theme_from_settings, WindowId, WindowRequest},
 };
 
 use taskbar::TaskbarIndicator;
@@ -91,6 +98,21 @@ impl<T> EventLoopWindowTarget<T> {
     })
   }
 
+  pub fn current_monitor(&self) -> Option<RootMonitorHandle> {
+    // `.window()` returns `None` if the window is invisible;
+    // we fallback to the primary monitor
+    let monitor = self
+      .app
+      .active_window()
+      .and_then(|window| window.window())
+      .and_then(|window| self.display.monitor_at_window(&window))
+      .or_else(|| self.display.primary_monitor());
+
+    monitor.map(|monitor| RootMonitorHandle {
+      inner: MonitorHandle { monitor },
+    })
+  }
+
   #[cfg(feature = "rwh_05")]
   pub fn raw_display_handle_rwh_05(&self) -> rwh_05::RawDisplayHandle {
     if self.is_wayland() {
@@ -160,6 +182,23 @@ impl<T> EventLoopWindowTarget<T> {
     }
   }
 
+  #[inline]
+  pub fn set_busy_cursor(&self, busy: bool) {
+    if let Err(e) = self
+      .window_requests_tx
+      .send((WindowId::dummy(), WindowRequest::BusyCursor(busy)))
+    {
+      log::warn!("Fail to send busy cursor request: {e}");
+    }
+  }
+
+  #[inline]
+  pub fn theme(&self) -> Theme {
+    Settings::default()
+      .map(|settings| theme_from_settings(&settings))
+      .unwrap_or_default()
+  }
+
   #[inline]
   pub fn set_theme(&self, theme: Option<Theme>) {
     if let Err(e) = self
@@ -180,8 +219,14 @@ pub struct EventLoop<T: 'static> {
   events: crossbeam_channel::Receiver<Event<'static, T>>,
   /// Draw queue of EventLoop
   draws: crossbeam_channel::Receiver<WindowId>,
+  /// Scale factor changes of windows, with their new inner size. They can't go through the event
+  /// queue since `WindowEvent::ScaleFactorChanged` borrows the new inner size.
+  scale_factor_changes: crossbeam_channel::Receiver<(WindowId, f64, PhysicalSize<u32>)>,
   /// Boolean to control device event thread
   run_device_thread: Option<Rc<AtomicBool>>,
+  /// Whether the application was already activated by `pump_events`
+  #[cfg(feature = "test-util")]
+  activated: bool,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
@@ -214,6 +259,7 @@ impl<T: 'static> EventLoop<T> {
     // Send StartCause::Init event
     let (event_tx, event_rx) = crossbeam_channel::unbounded();
     let (draw_tx, draw_rx) = crossbeam_channel::unbounded();
+    let (scale_factor_tx, scale_factor_rx) = crossbeam_channel::unbounded();
     let event_tx_ = event_tx.clone();
     app.connect_activate(move |_| {
       if let Err(e) = event_tx_.send(Event::NewEvents(StartCause::Init)) {
@@ -264,6 +310,27 @@ impl<T: 'static> EventLoop<T> {
     let mut taskbar = TaskbarIndicator::new();
     let is_wayland = window_target.is_wayland();
 
+    // IME context of each window and whether IME is allowed for it
+    let ime_contexts: Rc<RefCell<HashMap<WindowId, (gtk::IMContextSimple, Rc<Cell<bool>>)>>> =
+      Default::default();
+
+    // Region of each window that acts as a title bar
+    let drag_regions: Rc<RefCell<HashMap<WindowId, (LogicalPosition<f64>, LogicalSize<f64>)>>> =
+      Default::default();
+
+    // Thickness of the resize edges of each undecorated window, in logical pixels
+    let resize_borders: Rc<RefCell<HashMap<WindowId, f64>>> = Default::default();
+
+    // Handler set with `Window::set_hit_test_handler` for each window
+    let hit_test_handlers: Rc<RefCell<HashMap<WindowId, HitTestHandler>>> = Default::default();
+
+    // Cursor requested by each window, and whether the busy cursor replaces them all
+    let mut cursors: HashMap<WindowId, Option<Cursor>> = HashMap::new();
+    let mut busy_cursor = false;
+
+    // Geometry and decorations of the windows covering their monitor's work area, to restore them
+    let mut work_area_saved: HashMap<WindowId, (i32, i32, i32, i32, bool)> = HashMap::new();
+
     // Window Request
     window_requests_rx.attach(Some(&context), move |(id, request)| {
       if let Some(window) = app_.window_by_id(id.0) {
@@ -276,16 +343,121 @@ impl<T: 'static> EventLoop<T> {
           }
           WindowRequest::Visible(visible) => {
             if visible {
-              window.show_all();
+              util::show_window(&window, true);
             } else {
               window.hide();
             }
           }
+          WindowRequest::ShowWithoutFocus => util::show_window(&window, false),
+          WindowRequest::ImeAllowed(allowed) => {
+            if let Some((ime, ime_allowed)) = ime_contexts.borrow().get(&id) {
+              ime_allowed.set(allowed);
+              if allowed {
+                ime.focus_in();
+              } else {
+                // drop any pending composition
+                ime.reset();
+                ime.focus_out();
+              }
+            }
+          }
           WindowRequest::Focus => {
             window.present_with_time(gdk::ffi::GDK_CURRENT_TIME as _);
           }
-          WindowRequest::Resizable(resizable) => window.set_resizable(resizable),
-          WindowRequest::Closable(closable) => window.set_deletable(closable),
+          WindowRequest::Activate => {
+            if !window.is_visible() {
+              util::show_window(&window, true);
+            }
+            window.deiconify();
+            window.present_with_time(gdk::ffi::GDK_CURRENT_TIME as _);
+          }
+          WindowRequest::DragRegion(region) => match region {
+            Some(region) => {
+              drag_regions.borrow_mut().insert(id, region);
+            }
+            None => {
+              drag_regions.borrow_mut().remove(&id);
+            }
+          },
+          WindowRequest::ResizeBorderThickness(thickness) => match thickness {
+            Some(thickness) => {
+              resize_borders.borrow_mut().insert(id, thickness);
+            }
+            None => {
+              resize_borders.borrow_mut().remove(&id);
+            }
+          },
+          WindowRequest::HitTestHandler(handler) => match handler {
+            Some(handler) => {
+              hit_test_handlers.borrow_mut().insert(id, handler);
+            }
+            None => {
+              hit_test_handlers.borrow_mut().remove(&id);
+            }
+          },
+          WindowRequest::Resizable(resizable) => {
+            window.set_resizable(resizable);
+            util::update_window_functions(&window);
+            // The edge cursors of undecorated windows are only updated on pointer motion, so
+            // refresh them right away instead.
+            if !window.is_decorated() && !busy_cursor {
+              if let Some(gdk_window) = window.window() {
+                if !resizable {
+                  let cursor = cursors.get(&id).cloned().flatten();
+                  gdk_window.set_cursor(cursor.as_ref());
+                } else if !window.is_maximized()
+                  && !gdk_window.state().contains(WindowState::FULLSCREEN)
+                {
+                  if let Some(pointer) = gdk_window
+                    .display()
+                    .default_seat()
+                    .and_then(|seat| seat.pointer())
+                  {
+                    let (_, cx, cy) = pointer.position();
+                    let (left, top) = gdk_window.position();
+                    // Same lookup as the motion handler, so windows with a custom hit test
+                    // handler get the edges it reports.
+                    let edge = match hit_test_handlers.borrow().get(&id) {
+                      Some(handler) => {
+                        let scale_factor = gdk_window.scale_factor() as f64;
+                        match handler(PhysicalPosition::new(
+                          (cx - left) as f64 * scale_factor,
+                          (cy - top) as f64 * scale_factor,
+                        )) {
+                          HitTestResult::Resize(direction) => Some(direction),
+                          _ => None,
+                        }
+                      }
+                      None => {
+                        let (right, bottom) =
+                          (left + gdk_window.width(), top + gdk_window.height());
+                        let border = resize_border(
+                          resize_borders.borrow().get(&id).copied(),
+                          gdk_window.scale_factor(),
+                        );
+                        crate::window::hit_test((left, top, right, bottom), cx, cy, border, border)
+                      }
+                    };
+                    if let Some(edge) = edge {
+                      gdk_window.set_cursor(
+                        Cursor::from_name(&gdk_window.display(), edge.to_cursor_str()).as_ref(),
+                      );
+                    }
+                  }
+                }
+              }
+            }
+          }
+          WindowRequest::Minimizable(minimizable) => {
+            util::set_minimizable(&window, minimizable);
+          }
+          WindowRequest::Maximizable(maximizable) => {
+            util::set_maximizable(&window, maximizable);
+          }
+          WindowRequest::Closable(closable) => {
+            window.set_deletable(closable);
+            util::update_window_functions(&window);
+          }
           WindowRequest::Minimized(minimized) => {
             if minimized {
               window.iconify();
@@ -330,37 +502,71 @@ impl<T: 'static> EventLoop<T> {
               );
             }
           }
-          WindowRequest::Fullscreen(fullscreen) => match fullscreen {
-            Some(f) => {
-              if let Fullscreen::Borderless(m) = f {
-                if let Some(monitor) = m {
-                  let display = window.display();
-                  let monitor = monitor.inner;
-                  let monitors = display.n_monitors();
-                  for i in 0..monitors {
-                    let m = display.monitor(i).unwrap();
-                    if m == monitor.monitor {
-                      let screen = display.default_screen();
-                      window.fullscreen_on_monitor(&screen, i);
+          WindowRequest::Fullscreen(fullscreen) => {
+            // leave the work area first, whatever comes next
+            if let Some((x, y, w, h, decorated)) = work_area_saved.remove(&id) {
+              window.set_decorated(decorated);
+              window.move_(x, y);
+              window.resize(w, h);
+            }
+            match fullscreen {
+              Some(Fullscreen::BorderlessWorkArea(m)) => {
+                window.unfullscreen();
+                let display = window.display();
+                let monitor = match m {
+                  Some(monitor) => Some(monitor.inner.monitor),
+                  None => window
+                    .window()
+                    .and_then(|gdk_window| display.monitor_at_window(&gdk_window))
+                    .or_else(|| display.primary_monitor()),
+                };
+                if let Some(monitor) = monitor {
+                  let (x, y) = window.position();
+                  let (w, h) = window.size();
+                  work_area_saved.insert(id, (x, y, w, h, window.is_decorated()));
+
+                  let area = monitor.workarea();
+                  window.set_decorated(false);
+                  window.move_(area.x(), area.y());
+                  window.resize(area.width(), area.height());
+                }
+              }
+              Some(f) => {
+                if let Fullscreen::Borderless(m) = f {
+                  if let Some(monitor) = m {
+                    let display = window.display();
+                    let monitor = monitor.inner;
+                    let monitors = display.n_monitors();
+                    for i in 0..monitors {
+                      let m = display.monitor(i).unwrap();
+                      if m == monitor.monitor {
+                        let screen = display.default_screen();
+                        window.fullscreen_on_monitor(&screen, i);
+                      }
                     }
+                  } else {
+                    window.fullscreen();
                   }
-                } else {
-                  window.fullscreen();
                 }
               }
+              None => window.unfullscreen(),
             }
-            None => window.unfullscreen(),
-          },
+          }
           WindowRequest::Decorations(decorations) => window.set_decorated(decorations),
           WindowRequest::AlwaysOnBottom(always_on_bottom) => {
             window.set_keep_below(always_on_bottom)
           }
           WindowRequest::AlwaysOnTop(always_on_top) => window.set_keep_above(always_on_top),
-          WindowRequest::WindowIcon(window_icon) => {
-            if let Some(icon) = window_icon {
-              window.set_icon(Some(&icon.inner.into()));
+          WindowRequest::WindowIcon(window_icon) => match window_icon {
+            Some(icon) => window.set_icon(Some(&icon.inner.into())),
+            // Falls back to the default icon set on the application or the desktop theme.
+            None => {
+              window.set_icon(None);
+              if is_wayland {
+                util::set_wayland_icon_name(&window, app_.application_id().as_deref());
+              }
             }
-          }
+          },
           WindowRequest::UserAttention(request_type) => {
             window.set_urgency_hint(request_type.is_some())
           }
@@ -376,16 +582,18 @@ impl<T: 'static> EventLoop<T> {
             }
           }
           WindowRequest::CursorIcon(cursor) => {
-            if let Some(gdk_window) = window.window() {
-              let display = window.display();
-              match cursor {
-                Some(cr) => {
-                  gdk_window.set_cursor(Cursor::from_name(&display, cr.to_str()).as_ref())
-                }
-                None => gdk_window
-                  .set_cursor(Cursor::for_display(&display, CursorType::BlankCursor).as_ref()),
-              }
+            let display = window.display();
+            let cursor = match cursor {
+              Some(cr) => Cursor::from_name(&display, cr.to_str()),
+              None => Cursor::for_display(&display, CursorType::BlankCursor),
             };
+            // the busy cursor wins, this one is applied once it's gone
+            if !busy_cursor {
+              if let Some(gdk_window) = window.window() {
+                gdk_window.set_cursor(cursor.as_ref());
+              }
+            }
+            cursors.insert(id, cursor);
           }
           WindowRequest::CursorPosition((x, y)) => {
             if let Some(cursor) = window
@@ -411,6 +619,7 @@ impl<T: 'static> EventLoop<T> {
           }
           WindowRequest::ProgressBarState(_) => unreachable!(),
           WindowRequest::SetTheme(_) => unreachable!(),
+          WindowRequest::BusyCursor(_) => unreachable!(),
           WindowRequest::WireUpEvents {
             transparent,
             fullscreen,
@@ -441,21 +650,38 @@ impl<T: 'static> EventLoop<T> {
 
             // Allow resizing unmaximized non-fullscreen undecorated window
             let fullscreen_ = fullscreen.clone();
+            let resize_borders_ = resize_borders.clone();
+            let hit_test_handlers_ = hit_test_handlers.clone();
             window.connect_motion_notify_event(move |window, event| {
               if !window.is_decorated() && window.is_resizable() && !window.is_maximized() {
                 if let Some(window) = window.window() {
-                  let (cx, cy) = event.root();
-                  let (left, top) = window.position();
-                  let (w, h) = (window.width(), window.height());
-                  let (right, bottom) = (left + w, top + h);
-                  let border = window.scale_factor() * 5;
-                  let edge = crate::window::hit_test(
-                    (left, top, right, bottom),
-                    cx as _,
-                    cy as _,
-                    border,
-                    border,
-                  );
+                  let edge = match hit_test_handlers_.borrow().get(&id) {
+                    Some(handler) => {
+                      let (x, y) = event.position();
+                      let scale_factor = window.scale_factor() as f64;
+                      match handler(PhysicalPosition::new(x * scale_factor, y * scale_factor)) {
+                        HitTestResult::Resize(direction) => Some(direction),
+                        _ => None,
+                      }
+                    }
+                    None => {
+                      let (cx, cy) = event.root();
+                      let (left, top) = window.position();
+                      let (w, h) = (window.width(), window.height());
+                      let (right, bottom) = (left + w, top + h);
+                      let border = resize_border(
+                        resize_borders_.borrow().get(&id).copied(),
+                        window.scale_factor(),
+                      );
+                      crate::window::hit_test(
+                        (left, top, right, bottom),
+                        cx as _,
+                        cy as _,
+                        border,
+                        border,
+                      )
+                    }
+                  };
 
                   let edge = match &edge {
                     Some(e) if !fullscreen_.load(Ordering::Relaxed) => e.to_cursor_str(),
@@ -466,8 +692,53 @@ impl<T: 'static> EventLoop<T> {
               }
               glib::Propagation::Proceed
             });
+            let drag_regions_ = drag_regions.clone();
+            let resize_borders_ = resize_borders.clone();
+            let hit_test_handlers_ = hit_test_handlers.clone();
             window.connect_button_press_event(move |window, event| {
               const LMB: u32 = 1;
+              let begin_resize_drag = |direction: ResizeDirection| {
+                // `begin_resize_drag` keeps the current cursor, show the resize cursor for the
+                // whole drag. The motion handler of undecorated windows puts the regular cursor
+                // back afterwards.
+                if !window.is_decorated() {
+                  if let Some(gdk_window) = window.window() {
+                    let cursor = Cursor::from_name(&window.display(), direction.to_cursor_str());
+                    gdk_window.set_cursor(cursor.as_ref());
+                  }
+                }
+                let (cx, cy) = event.root();
+                window.begin_resize_drag(
+                  direction.to_gtk_edge(),
+                  LMB as i32,
+                  cx as i32,
+                  cy as i32,
+                  event.time(),
+                );
+              };
+
+              // The hit test handler replaces the resize edges and the drag region
+              if event.button() == LMB {
+                if let Some(handler) = hit_test_handlers_.borrow().get(&id) {
+                  let (x, y) = event.position();
+                  let scale_factor = window.scale_factor() as f64;
+                  match handler(PhysicalPosition::new(x * scale_factor, y * scale_factor)) {
+                    HitTestResult::Drag if event.event_type() == gdk::EventType::ButtonPress => {
+                      let (cx, cy) = event.root();
+                      window.begin_move_drag(LMB as i32, cx as i32, cy as i32, event.time());
+                      return glib::Propagation::Stop;
+                    }
+                    HitTestResult::Resize(direction)
+                      if window.is_resizable() && !window.is_maximized() =>
+                    {
+                      begin_resize_drag(direction);
+                    }
+                    _ => (),
+                  }
+                  return glib::Propagation::Proceed;
+                }
+              }
+
               if (is_wayland || !window.is_decorated())
                 && window.is_resizable()
                 && !window.is_maximized()
@@ -477,30 +748,46 @@ impl<T: 'static> EventLoop<T> {
                 let (left, top) = window.position();
                 let (w, h) = window.size();
                 let (right, bottom) = (left + w, top + h);
-                let border = window.scale_factor() * 5;
-                let edge = crate::window::hit_test(
+                let border = resize_border(
+                  resize_borders_.borrow().get(&id).copied(),
+                  window.scale_factor(),
+                );
+                let direction = crate::window::hit_test(
                   (left, top, right, bottom),
                   cx as _,
                   cy as _,
                   border,
                   border,
-                )
-                .map(|d| d.to_gtk_edge())
-                // we return `WindowEdge::__Unknown` to be ignored later.
-                // we must return 8 or bigger, otherwise it will be the same as one of the other 7 variants of `WindowEdge` enum.
-                .unwrap_or(WindowEdge::__Unknown(8));
-                // Ignore the `__Unknown` variant so the window receives the click correctly if it is not on the edges.
-                match edge {
-                  WindowEdge::__Unknown(_) => (),
-                  _ => {
-                    // FIXME: calling `window.begin_resize_drag` uses the default cursor, it should show a resizing cursor instead
-                    window.begin_resize_drag(edge, LMB as i32, cx as i32, cy as i32, event.time())
+                );
+                // The window receives the click normally if it is not on the edges.
+                if let Some(direction) = direction {
+                  begin_resize_drag(direction);
+                  return glib::Propagation::Proceed;
+                }
+              }
+
+              // The drag region acts as a title bar
+              if event.button() == LMB && event.event_type() == gdk::EventType::ButtonPress {
+                let (x, y) = event.position();
+                let in_drag_region = match drag_regions_.borrow().get(&id) {
+                  Some((position, size)) => {
+                    x >= position.x
+                      && x < position.x + size.width
+                      && y >= position.y
+                      && y < position.y + size.height
                   }
+                  None => false,
+                };
+                if in_drag_region {
+                  let (cx, cy) = event.root();
+                  window.begin_move_drag(LMB as i32, cx as i32, cy as i32, event.time());
+                  return glib::Propagation::Stop;
                 }
               }
 
               glib::Propagation::Proceed
             });
+            let resize_borders_ = resize_borders.clone();
             window.connect_touch_event(move |window, event| {
               if !window.is_decorated() && window.is_resizable() && !window.is_maximized() {
                 if let Some(window) = window.window() {
@@ -509,7 +796,10 @@ impl<T: 'static> EventLoop<T> {
                       let (left, top) = window.position();
                       let (w, h) = (window.width(), window.height());
                       let (right, bottom) = (left + w, top + h);
-                      let border = window.scale_factor() * 5;
+                      let border = resize_border(
+                        resize_borders_.borrow().get(&id).copied(),
+                        window.scale_factor(),
+                      );
                       let edge = crate::window::hit_test(
                         (left, top, right, bottom),
                         cx as _,
@@ -553,6 +843,19 @@ impl<T: 'static> EventLoop<T> {
               glib::Propagation::Stop
             });
 
+            let scale_factor_tx = scale_factor_tx.clone();
+            window.connect_scale_factor_notify(move |window| {
+              let scale_factor = window.scale_factor() as f64;
+              let (w, h) = window.size();
+              if let Err(e) = scale_factor_tx.send((
+                id,
+                scale_factor,
+                LogicalSize::new(w, h).to_physical(scale_factor),
+              )) {
+                log::warn!("Failed to send scale factor change to event channel: {}", e);
+              }
+            });
+
             let tx_clone = event_tx.clone();
             window.connect_configure_event(move |window, event| {
               let scale_factor = window.scale_factor();
@@ -582,8 +885,16 @@ impl<T: 'static> EventLoop<T> {
               false
             });
 
+            // The focus last reported, so the initial focus sent below isn't repeated when GTK
+            // delivers its own focus-in event for it.
+            let focused = Rc::new(Cell::new(false));
+
             let tx_clone = event_tx.clone();
+            let focused_ = focused.clone();
             window.connect_focus_in_event(move |_, _| {
+              if focused_.replace(true) {
+                return glib::Propagation::Proceed;
+              }
               if let Err(e) = tx_clone.send(Event::WindowEvent {
                 window_id: RootWindowId(id),
                 event: WindowEvent::Focused(true),
@@ -597,7 +908,11 @@ impl<T: 'static> EventLoop<T> {
             });
 
             let tx_clone = event_tx.clone();
+            let focused_ = focused.clone();
             window.connect_focus_out_event(move |_, _| {
+              if !focused_.replace(false) {
+                return glib::Propagation::Proceed;
+              }
               if let Err(e) = tx_clone.send(Event::WindowEvent {
                 window_id: RootWindowId(id),
                 event: WindowEvent::Focused(false),
@@ -610,6 +925,75 @@ impl<T: 'static> EventLoop<T> {
               glib::Propagation::Proceed
             });
 
+            // the window may have been focused before the handlers above were connected
+            if window.is_active() && !focused.replace(true) {
+              if let Err(e) = event_tx.send(Event::WindowEvent {
+                window_id: RootWindowId(id),
+                event: WindowEvent::Focused(true),
+              }) {
+                log::warn!(
+                  "Failed to send window focus-in event to event channel: {}",
+                  e
+                );
+              }
+            }
+
+            window.drag_dest_set(
+              gtk::DestDefaults::ALL,
+              &[
+                gtk::TargetEntry::new("text/uri-list", gtk::TargetFlags::OTHER_APP, 0),
+                gtk::TargetEntry::new("text/plain", gtk::TargetFlags::OTHER_APP, 1),
+              ],
+              gdk::DragAction::COPY,
+            );
+            let tx_clone = event_tx.clone();
+            window.connect_drag_data_received(move |_, _, _, _, data, _, _| {
+              let uris = data.uris();
+              let events: Vec<_> = if uris.is_empty() {
+                data
+                  .text()
+                  .map(|text| WindowEvent::DroppedText(text.into()))
+                  .into_iter()
+                  .collect()
+              } else {
+                uris
+                  .iter()
+                  .filter_map(|uri| url::Url::parse(uri).ok())
+                  .map(|url| match url.to_file_path() {
+                    Ok(path) if url.scheme() == "file" => WindowEvent::DroppedFile(path),
+                    _ => WindowEvent::DroppedUrl(url),
+                  })
+                  .collect()
+              };
+              for event in events {
+                if let Err(e) = tx_clone.send(Event::WindowEvent {
+                  window_id: RootWindowId(id),
+                  event,
+                }) {
+                  log::warn!("Failed to send window drop event to event channel: {}", e);
+                }
+              }
+            });
+
+            // Connected after the handler updating `Window::theme`, so the getter already returns
+            // the new theme when the event is received.
+            let tx_clone = event_tx.clone();
+            let last_theme = Cell::new(
+              Settings::default()
+                .map(|settings| theme_from_settings(&settings))
+                .unwrap_or_default(),
+            );
+            connect_theme_changed(&window, move |theme| {
+              if last_theme.replace(theme) != theme {
+                if let Err(e) = tx_clone.send(Event::WindowEvent {
+                  window_id: RootWindowId(id),
+                  event: WindowEvent::ThemeChanged(theme),
+                }) {
+                  log::warn!("Failed to send theme changed event to event channel: {}", e);
+                }
+              }
+            });
+
             let tx_clone = event_tx.clone();
             window.connect_destroy(move |_| {
               if let Err(e) = tx_clone.send(Event::WindowEvent {
@@ -650,8 +1034,7 @@ impl<T: 'static> EventLoop<T> {
                     event: WindowEvent::CursorMoved {
                       position: LogicalPosition::new(x, y).to_physical(scale_factor as f64),
                       device_id: DEVICE_ID,
-                      // this field is depracted so it is fine to pass empty state
-                      modifiers: ModifiersState::empty(),
+                      modifiers: keyboard::modifiers_from_state(motion.state()),
                     },
                   }) {
                     log::warn!("Failed to send cursor moved event to event channel: {}", e);
@@ -688,8 +1071,7 @@ impl<T: 'static> EventLoop<T> {
                   },
                   state: ElementState::Pressed,
                   device_id: DEVICE_ID,
-                  // this field is depracted so it is fine to pass empty state
-                  modifiers: ModifiersState::empty(),
+                  modifiers: keyboard::modifiers_from_state(event.state()),
                 },
               }) {
                 log::warn!(
@@ -714,8 +1096,7 @@ impl<T: 'static> EventLoop<T> {
                   },
                   state: ElementState::Released,
                   device_id: DEVICE_ID,
-                  // this field is depracted so it is fine to pass empty state
-                  modifiers: ModifiersState::empty(),
+                  modifiers: keyboard::modifiers_from_state(event.state()),
                 },
               }) {
                 log::warn!(
@@ -738,7 +1119,7 @@ impl<T: 'static> EventLoop<T> {
                     ScrollDirection::Smooth => TouchPhase::Moved,
                     _ => TouchPhase::Ended,
                   },
-                  modifiers: ModifiersState::empty(),
+                  modifiers: keyboard::modifiers_from_state(event.state()),
                 },
               }) {
                 log::warn!("Failed to send scroll event to event channel: {}", e);
@@ -794,6 +1175,20 @@ impl<T: 'static> EventLoop<T> {
             let ime = gtk::IMContextSimple::default();
             ime.set_client_window(window.window().as_ref());
             ime.focus_in();
+            let ime_allowed = Rc::new(Cell::new(true));
+            ime_contexts
+              .borrow_mut()
+              .insert(id, (ime.clone(), ime_allowed.clone()));
+            let ime_contexts_ = ime_contexts.clone();
+            let drag_regions_ = drag_regions.clone();
+            let resize_borders_ = resize_borders.clone();
+            let hit_test_handlers_ = hit_test_handlers.clone();
+            window.connect_destroy(move |_| {
+              ime_contexts_.borrow_mut().remove(&id);
+              drag_regions_.borrow_mut().remove(&id);
+              resize_borders_.borrow_mut().remove(&id);
+              hit_test_handlers_.borrow_mut().remove(&id);
+            });
             ime.connect_commit(move |_, s| {
               if let Err(e) = tx_clone.send(Event::WindowEvent {
                 window_id: RootWindowId(id),
@@ -809,7 +1204,9 @@ impl<T: 'static> EventLoop<T> {
             let handler = keyboard_handler.clone();
             window.connect_key_press_event(move |_, event_key| {
               handler(event_key.to_owned(), ElementState::Pressed);
-              ime.filter_keypress(event_key);
+              if ime_allowed.get() {
+                ime.filter_keypress(event_key);
+              }
 
               glib::Propagation::Proceed
             });
@@ -821,8 +1218,58 @@ impl<T: 'static> EventLoop<T> {
             });
 
             let tx_clone = event_tx.clone();
+            let window_mode = Cell::new(WindowMode::Normal);
             window.connect_window_state_event(move |window, event| {
+              let new_state = event.new_window_state();
+              let mode = if new_state.contains(WindowState::ICONIFIED) {
+                WindowMode::Minimized
+              } else if new_state.contains(WindowState::FULLSCREEN) {
+                WindowMode::Fullscreen
+              } else if new_state.contains(WindowState::MAXIMIZED) {
+                WindowMode::Maximized
+              } else {
+                WindowMode::Normal
+              };
+              if window_mode.replace(mode) != mode {
+                if let Err(e) = tx_clone.send(Event::WindowEvent {
+                  window_id: RootWindowId(id),
+                  event: WindowEvent::WindowModeChanged(mode),
+                }) {
+                  log::warn!(
+                    "Failed to send window mode changed event to event channel: {}",
+                    e
+                  );
+                }
+              }
+
               let state = event.changed_mask();
+              if state.contains(WindowState::ICONIFIED) {
+                let minimized = event.new_window_state().contains(WindowState::ICONIFIED);
+                if let Err(e) = tx_clone.send(Event::WindowEvent {
+                  window_id: RootWindowId(id),
+                  event: WindowEvent::Minimized(minimized),
+                }) {
+                  log::warn!(
+                    "Failed to send window minimized event to event channel: {}",
+                    e
+                  );
+                }
+              }
+
+              let occluded_mask = WindowState::ICONIFIED | WindowState::WITHDRAWN;
+              if state.intersects(occluded_mask) {
+                let occluded = new_state.intersects(occluded_mask);
+                if let Err(e) = tx_clone.send(Event::WindowEvent {
+                  window_id: RootWindowId(id),
+                  event: WindowEvent::Occluded(occluded),
+                }) {
+                  log::warn!(
+                    "Failed to send window occluded event to event channel: {}",
+                    e
+                  );
+                }
+              }
+
               if state.contains(WindowState::ICONIFIED) || state.contains(WindowState::MAXIMIZED) {
                 let scale_factor = window.scale_factor();
 
@@ -878,8 +1325,27 @@ impl<T: 'static> EventLoop<T> {
           WindowRequest::SetTheme(theme) => {
             if let Some(settings) = Settings::default() {
               match theme {
-                Some(Theme::Dark) => settings.set_gtk_application_prefer_dark_theme(true),
-                Some(Theme::Light) | None => settings.set_gtk_application_prefer_dark_theme(false),
+                Some(theme) => apply_theme(&settings, theme),
+                None => settings.set_gtk_application_prefer_dark_theme(false),
+              }
+            }
+          }
+          WindowRequest::BusyCursor(busy) => {
+            busy_cursor = busy;
+            for window in app_.windows() {
+              let gdk_window = match window.window() {
+                Some(gdk_window) => gdk_window,
+                None => continue,
+              };
+              if busy {
+                let cursor = Cursor::from_name(&window.display(), CursorIcon::Wait.to_str());
+                gdk_window.set_cursor(cursor.as_ref());
+              } else {
+                let id = window
+                  .downcast_ref::<gtk::ApplicationWindow>()
+                  .map(|window| WindowId(window.id()));
+                let cursor = id.and_then(|id| cursors.get(&id).cloned().flatten());
+                gdk_window.set_cursor(cursor.as_ref());
               }
             }
           }
@@ -893,12 +1359,20 @@ impl<T: 'static> EventLoop<T> {
     let event_loop = Self {
       window_target: RootELW {
         p: window_target,
+        control_flow: Default::default(),
+        busy_cursor_depth: Default::default(),
+        resize_debouncer: Default::default(),
+        #[cfg(feature = "trace")]
+        stats: Default::default(),
         _marker: std::marker::PhantomData,
       },
       user_event_tx,
       events: event_rx,
       draws: draw_rx,
+      scale_factor_changes: scale_factor_rx,
       run_device_thread,
+      #[cfg(feature = "test-util")]
+      activated: false,
     };
 
     Ok(event_loop)
@@ -946,6 +1420,7 @@ impl<T: 'static> EventLoop<T> {
   where
     F: FnMut(Event<'_, T>, &RootELW<T>, &mut ControlFlow),
   {
+    #[cfg_attr(feature = "trace", derive(Debug, Clone, Copy, PartialEq))]
     enum EventState {
       NewStart,
       EventQueue,
@@ -961,12 +1436,15 @@ impl<T: 'static> EventLoop<T> {
         let window_target = &self.window_target;
         let events = &self.events;
         let draws = &self.draws;
+        let scale_factor_changes = &self.scale_factor_changes;
 
         window_target.p.app.activate();
 
         let mut state = EventState::NewStart;
         let exit_code = loop {
           let mut blocking = false;
+          #[cfg(feature = "trace")]
+          let previous_state = state;
           match state {
             EventState::NewStart => match control_flow {
               ControlFlow::ExitWithCode(code) => {
@@ -974,7 +1452,7 @@ impl<T: 'static> EventLoop<T> {
                 break code;
               }
               ControlFlow::Wait => {
-                if !events.is_empty() {
+                if !events.is_empty() || !scale_factor_changes.is_empty() {
                   callback(
                     Event::NewEvents(StartCause::WaitCancelled {
                       start: Instant::now(),
@@ -1000,7 +1478,7 @@ impl<T: 'static> EventLoop<T> {
                     &mut control_flow,
                   );
                   state = EventState::EventQueue;
-                } else if !events.is_empty() {
+                } else if !events.is_empty() || !scale_factor_changes.is_empty() {
                   callback(
                     Event::NewEvents(StartCause::WaitCancelled {
                       start,
@@ -1028,16 +1506,22 @@ impl<T: 'static> EventLoop<T> {
                 callback(Event::LoopDestroyed, window_target, &mut control_flow);
                 break (code);
               }
-              _ => match events.try_recv() {
-                Ok(event) => match event {
-                  Event::LoopDestroyed => control_flow = ControlFlow::ExitWithCode(1),
-                  _ => callback(event, window_target, &mut control_flow),
-                },
-                Err(_) => {
-                  callback(Event::MainEventsCleared, window_target, &mut control_flow);
-                  state = EventState::DrawQueue;
+              _ => {
+                if let Ok(change) = scale_factor_changes.try_recv() {
+                  send_scale_factor_change(change, &mut callback, window_target, &mut control_flow);
+                } else {
+                  match events.try_recv() {
+                    Ok(event) => match event {
+                      Event::LoopDestroyed => control_flow = ControlFlow::ExitWithCode(1),
+                      _ => callback(event, window_target, &mut control_flow),
+                    },
+                    Err(_) => {
+                      callback(Event::MainEventsCleared, window_target, &mut control_flow);
+                      state = EventState::DrawQueue;
+                    }
+                  }
                 }
-              },
+              }
             },
             EventState::DrawQueue => match control_flow {
               ControlFlow::ExitWithCode(code) => {
@@ -1057,6 +1541,17 @@ impl<T: 'static> EventLoop<T> {
               }
             },
           }
+          #[cfg(feature = "trace")]
+          if state != previous_state {
+            log::trace!(
+              "Event loop state changed from {:?} to {:?}",
+              previous_state,
+              state
+            );
+            if state == EventState::EventQueue {
+              window_target.record_queue_depth(events.len());
+            }
+          }
           gtk::main_iteration_do(blocking);
         };
         if let Some(run_device_thread) = run_device_thread {
@@ -1067,6 +1562,126 @@ impl<T: 'static> EventLoop<T> {
       .unwrap_or(1)
   }
 
+  /// Runs a single `NewStart` -> `EventQueue` -> `DrawQueue` pass of the state machine of
+  /// [`Self::run_return`], waiting for up to `timeout` first if there is nothing to process.
+  #[cfg(feature = "test-util")]
+  pub(crate) fn pump_events<F>(&mut self, timeout: Option<Duration>, mut callback: F) -> PumpStatus
+  where
+    F: FnMut(Event<'_, T>, &RootELW<T>, &mut ControlFlow),
+  {
+    let context = MainContext::default();
+    let run_device_thread = self.run_device_thread.clone();
+
+    context
+      .with_thread_default(|| {
+        let mut control_flow = ControlFlow::Poll;
+        let window_target = &self.window_target;
+        let events = &self.events;
+        let draws = &self.draws;
+        let scale_factor_changes = &self.scale_factor_changes;
+        let exiting =
+          |control_flow: &ControlFlow| matches!(control_flow, ControlFlow::ExitWithCode(_));
+
+        // Activating the application queues `StartCause::Init`, which replaces the
+        // `NewEvents` of the first pass.
+        let init = !self.activated;
+        if init {
+          window_target.p.app.activate();
+          self.activated = true;
+        }
+
+        let start = Instant::now();
+        while gtk::events_pending() {
+          gtk::main_iteration_do(false);
+        }
+        if events.is_empty() && draws.is_empty() && scale_factor_changes.is_empty() {
+          match timeout {
+            Some(timeout) if timeout.is_zero() => (),
+            Some(timeout) => {
+              let timed_out = Rc::new(Cell::new(false));
+              let timed_out_ = timed_out.clone();
+              let source = glib::timeout_add_local_once(timeout, move || timed_out_.set(true));
+              gtk::main_iteration_do(true);
+              if !timed_out.get() {
+                source.remove();
+              }
+            }
+            None => {
+              gtk::main_iteration_do(true);
+            }
+          }
+          while gtk::events_pending() {
+            gtk::main_iteration_do(false);
+          }
+        }
+
+        if !init {
+          let cause = match timeout {
+            Some(timeout) if timeout.is_zero() => StartCause::Poll,
+            Some(timeout) => {
+              let requested_resume = start + timeout;
+              if Instant::now() >= requested_resume {
+                StartCause::ResumeTimeReached {
+                  start,
+                  requested_resume,
+                }
+              } else {
+                StartCause::WaitCancelled {
+                  start,
+                  requested_resume: Some(requested_resume),
+                }
+              }
+            }
+            None => StartCause::WaitCancelled {
+              start,
+              requested_resume: None,
+            },
+          };
+          callback(Event::NewEvents(cause), window_target, &mut control_flow);
+        }
+
+        while !exiting(&control_flow) {
+          if let Ok(change) = scale_factor_changes.try_recv() {
+            send_scale_factor_change(change, &mut callback, window_target, &mut control_flow);
+            continue;
+          }
+          match events.try_recv() {
+            Ok(Event::LoopDestroyed) => control_flow = ControlFlow::ExitWithCode(1),
+            Ok(event) => callback(event, window_target, &mut control_flow),
+            Err(_) => break,
+          }
+        }
+        if !exiting(&control_flow) {
+          callback(Event::MainEventsCleared, window_target, &mut control_flow);
+        }
+        while !exiting(&control_flow) {
+          match draws.try_recv() {
+            Ok(id) => callback(
+              Event::RedrawRequested(RootWindowId(id)),
+              window_target,
+              &mut control_flow,
+            ),
+            Err(_) => break,
+          }
+        }
+        if !exiting(&control_flow) {
+          callback(Event::RedrawEventsCleared, window_target, &mut control_flow);
+        }
+
+        match control_flow {
+          ControlFlow::ExitWithCode(code) => {
+            callback(Event::LoopDestroyed, window_target, &mut control_flow);
+            if let Some(run_device_thread) = run_device_thread {
+              run_device_thread.store(false, Ordering::Relaxed);
+            }
+            PumpStatus::Exit(code)
+          }
+          _ => PumpStatus::Continue,
+        }
+      })
+      .unwrap_or(PumpStatus::Exit(1))
+  }
+
   #[inline]
   pub fn window_target(&self) -> &RootELW<T> {
     &self.window_target
@@ -1119,6 +1734,58 @@ impl<T: 'static> EventLoopProxy<T> {
   }
 }
 
+/// Sends `ScaleFactorChanged` followed by `Resized` for a scale factor change, and resizes the
+/// window if the callback changed `new_inner_size`, like `WM_DPICHANGED` does on Windows.
+fn send_scale_factor_change<T, F>(
+  (id, scale_factor, suggested_size): (WindowId, f64, PhysicalSize<u32>),
+  callback: &mut F,
+  window_target: &RootELW<T>,
+  control_flow: &mut ControlFlow,
+) where
+  F: FnMut(Event<'_, T>, &RootELW<T>, &mut ControlFlow),
+{
+  let mut new_inner_size = suggested_size;
+  callback(
+    Event::WindowEvent {
+      window_id: RootWindowId(id),
+      event: WindowEvent::ScaleFactorChanged {
+        scale_factor,
+        new_inner_size: &mut new_inner_size,
+      },
+    },
+    window_target,
+    control_flow,
+  );
+
+  if new_inner_size != suggested_size {
+    let (w, h) = new_inner_size.to_logical::<i32>(scale_factor).into();
+    if let Err(e) = window_target
+      .p
+      .window_requests_tx
+      .send((id, WindowRequest::Size((w, h))))
+    {
+      log::warn!("Fail to send size request: {}", e);
+    }
+  }
+
+  callback(
+    Event::WindowEvent {
+      window_id: RootWindowId(id),
+      event: WindowEvent::Resized(new_inner_size),
+    },
+    window_target,
+    control_flow,
+  );
+}
+
+/// Thickness of the resize edges of an undecorated window, in physical pixels.
+fn resize_border(thickness: Option<f64>, scale_factor: i32) -> i32 {
+  match thickness {
+    Some(thickness) => (thickness * scale_factor as f64).round() as i32,
+    None => scale_factor * 5,
+  }
+}
+
 fn assert_is_main_thread(suggested_method: &str) {
   assert!(
     is_main_thread(),
@@ -4,7 +4,7 @@
 
 use gtk::gdk_pixbuf::{Colorspace, Pixbuf};
 
-use crate::window::BadIcon;
+use crate::icon::{BadIcon, RgbaIcon};
 
 /// An icon used for the window titlebar, taskbar, etc.
 #[derive(Debug, Clone)]
@@ -35,8 +35,17 @@ impl PlatformIcon {
   /// The length of `rgba` must be divisible by 4, and `width * height` must equal
   /// `rgba.len() / 4`. Otherwise, this will return a `BadIcon` error.
   pub fn from_rgba(rgba: Vec<u8>, width: u32, height: u32) -> Result<Self, BadIcon> {
+    let RgbaIcon {
+      rgba,
+      width,
+      height,
+    } = RgbaIcon::from_rgba(rgba, width, height)?;
+
+    // `Pixbuf::from_mut_slice` panics if the buffer is shorter than `row_stride * height`, which
+    // the length check above rules out for packed 8-bit RGBA rows.
     let row_stride =
       Pixbuf::calculate_rowstride(Colorspace::Rgb, true, 8, width as i32, height as i32);
+
     Ok(Self {
       raw: rgba,
       width: width as i32,
@@ -104,6 +104,22 @@ pub(crate) fn raw_key_to_key(gdk_key: RawKey) -> Option<Key<'static>> {
     // KP_Separator? What does it map to?
     KP_Tab => Some(Key::Tab),
     KP_Up => Some(Key::ArrowUp),
+
+    // Dead keys carry the character inserted when pressing them twice, the composed text itself
+    // is committed by the input method.
+    dead_grave => Some(Key::Dead(Some('`'))),
+    dead_acute => Some(Key::Dead(Some('´'))),
+    dead_circumflex => Some(Key::Dead(Some('^'))),
+    dead_tilde => Some(Key::Dead(Some('~'))),
+    dead_macron => Some(Key::Dead(Some('¯'))),
+    dead_breve => Some(Key::Dead(Some('˘'))),
+    dead_abovedot => Some(Key::Dead(Some('˙'))),
+    dead_diaeresis => Some(Key::Dead(Some('¨'))),
+    dead_abovering => Some(Key::Dead(Some('˚'))),
+    dead_doubleacute => Some(Key::Dead(Some('˝'))),
+    dead_caron => Some(Key::Dead(Some('ˇ'))),
+    dead_cedilla => Some(Key::Dead(Some('¸'))),
+    dead_ogonek => Some(Key::Dead(Some('˛'))),
     // TODO: more mappings (media etc)
     _ => None,
   }
@@ -165,6 +181,24 @@ pub(crate) fn get_modifiers(key: EventKey) -> ModifiersState {
   result
 }
 
+// used for pointer events, which carry the modifier mask held down when they were generated.
+pub(crate) fn modifiers_from_state(state: gdk::ModifierType) -> ModifiersState {
+  let mut result = ModifiersState::empty();
+  if state.contains(gdk::ModifierType::SHIFT_MASK) {
+    result |= ModifiersState::SHIFT;
+  }
+  if state.contains(gdk::ModifierType::CONTROL_MASK) {
+    result |= ModifiersState::CONTROL;
+  }
+  if state.contains(gdk::ModifierType::MOD1_MASK) {
+    result |= ModifiersState::ALT;
+  }
+  if state.intersects(gdk::ModifierType::SUPER_MASK | gdk::ModifierType::MOD4_MASK) {
+    result |= ModifiersState::SUPER;
+  }
+  result
+}
+
 pub(crate) fn make_key_event(
   key: &EventKey,
   is_repeat: bool,
@@ -212,8 +246,7 @@ pub(crate) fn make_key_event(
   // make sure we have a valid key
   if !matches!(key_without_modifiers, Key::Unidentified(_)) {
     let location = raw_key_to_location(keyval_with_modifiers);
-    let text_with_all_modifiers =
-      text_without_modifiers.map(|text| insert_or_get_key_str(text.to_string()));
+    let text_with_all_modifiers = key_text(text_without_modifiers, &logical_key);
     return Some(KeyEvent {
       location,
       logical_key,
@@ -233,6 +266,14 @@ pub(crate) fn make_key_event(
   None
 }
 
+/// Returns the text inserted by pressing `logical_key`, which is `text` unless it's a dead key.
+fn key_text(text: Option<char>, logical_key: &Key<'static>) -> Option<&'static str> {
+  // Dead keys don't insert text on their own.
+  text
+    .filter(|_| !matches!(logical_key, Key::Dead(_)))
+    .map(|text| insert_or_get_key_str(text.to_string()))
+}
+
 /// Map a hardware keycode to a keyval by performing a lookup in the keymap and finding the
 /// keyval with the lowest group and level
 fn hardware_keycode_to_keyval(keycode: u16) -> Option<RawKey> {
@@ -274,3 +315,21 @@ fn hardware_keycode_to_keyval(keycode: u16) -> Option<RawKey> {
   }
   None
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn dead_keys_map_to_their_spacing_character() {
+    assert_eq!(raw_key_to_key(dead_acute), Some(Key::Dead(Some('´'))));
+    assert_eq!(raw_key_to_key(dead_diaeresis), Some(Key::Dead(Some('¨'))));
+  }
+
+  #[test]
+  fn dead_keys_insert_no_text() {
+    let dead_key = raw_key_to_key(dead_acute).unwrap();
+    assert_eq!(key_text(Some('´'), &dead_key), None);
+    assert_eq!(key_text(Some('a'), &Key::Character("a")), Some("a"));
+  }
+}
@@ -2,18 +2,67 @@
 // Copyright 2021-2023 Tauri Programme within The Commons Conservancy
 // SPDX-License-Identifier: Apache-2.0
 
-use gtk::gdk::{self, prelude::MonitorExt, Display};
+use std::{cmp::Ordering, hash::Hash};
+
+use gtk::gdk::{self, prelude::*, Display};
 
 use crate::{
   dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize},
-  monitor::{MonitorHandle as RootMonitorHandle, VideoMode as RootVideoMode},
+  monitor::{HdrMetadata, MonitorHandle as RootMonitorHandle, VideoMode as RootVideoMode},
 };
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone)]
 pub struct MonitorHandle {
   pub(crate) monitor: gdk::Monitor,
 }
 
+// `gdk::Monitor` objects are recreated when monitors are plugged in or out, so compare monitors
+// by the name of the connector they are plugged into instead. GDK 3 only exposes it on X11, as the
+// model of the monitor, so monitors on other backends fall back to the object itself.
+impl MonitorHandle {
+  fn connector(&self) -> Option<String> {
+    if self.monitor.display().is_some_and(|d| d.backend().is_x11()) {
+      self.monitor.model().map(|s| s.as_str().to_string())
+    } else {
+      None
+    }
+  }
+}
+
+impl PartialEq for MonitorHandle {
+  fn eq(&self, other: &Self) -> bool {
+    self.cmp(other) == Ordering::Equal
+  }
+}
+
+impl Eq for MonitorHandle {}
+
+impl PartialOrd for MonitorHandle {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for MonitorHandle {
+  fn cmp(&self, other: &Self) -> Ordering {
+    match (self.connector(), other.connector()) {
+      (Some(connector), Some(other_connector)) => connector.cmp(&other_connector),
+      (connector, other_connector) => connector
+        .cmp(&other_connector)
+        .then_with(|| self.monitor.cmp(&other.monitor)),
+    }
+  }
+}
+
+impl Hash for MonitorHandle {
+  fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    match self.connector() {
+      Some(connector) => connector.hash(state),
+      None => self.monitor.hash(state),
+    }
+  }
+}
+
 impl MonitorHandle {
   pub fn new(display: &gdk::Display, number: i32) -> Self {
     let monitor = display.monitor(number).unwrap();
@@ -54,6 +103,11 @@ impl MonitorHandle {
   pub fn video_modes(&self) -> Box<dyn Iterator<Item = RootVideoMode>> {
     Box::new(Vec::new().into_iter())
   }
+
+  #[inline]
+  pub fn hdr_metadata(&self) -> Option<HdrMetadata> {
+    None
+  }
 }
 
 unsafe impl Send for MonitorHandle {}
@@ -9,10 +9,13 @@ use gtk::{
     prelude::{DeviceExt, SeatExt},
     Display,
   },
-  glib::{self},
-  traits::{GtkWindowExt, WidgetExt},
+  glib::{self, prelude::ObjectExt},
+  traits::{ContainerExt, GtkWindowExt, WidgetExt},
+};
+use std::{
+  cell::{Cell, RefCell},
+  rc::Rc,
 };
-use std::{cell::RefCell, rc::Rc};
 
 #[inline]
 pub fn cursor_position(is_wayland: bool) -> Result<PhysicalPosition<f64>, ExternalError> {
@@ -74,6 +77,158 @@ pub fn set_size_constraints<W: GtkWindowExt + WidgetExt>(
   )
 }
 
+/// Names the window icon after the app id, so Wayland compositors that resolve icons by name
+/// find the one of the `.desktop` file matching the app id.
+///
+/// Setting an icon name replaces the icon set with `gtk_window_set_icon`, so this is only meant
+/// for windows without an icon of their own.
+pub fn set_wayland_icon_name<W: GtkWindowExt>(window: &W, app_id: Option<&str>) {
+  if let Some(app_id) = app_id {
+    window.set_icon_name(Some(app_id));
+  }
+}
+
+const CONTENT_SHOWN_KEY: &str = "tao-content-shown";
+
+/// Shows the window along with its whole widget tree the first time only, afterwards the child
+/// widgets hidden by the app, like a menu bar, are left hidden.
+///
+/// Windows that already have a visible child when first shown, like those created by the app
+/// itself, are considered as having their content shown.
+pub fn show_content<W: GtkWindowExt + WidgetExt + ContainerExt>(window: &W) {
+  // SAFETY: the key is only ever set to a `()`.
+  let content_shown = unsafe { window.data::<()>(CONTENT_SHOWN_KEY) }.is_some()
+    || window.children().iter().any(|child| child.is_visible());
+
+  if content_shown {
+    window.show();
+  } else {
+    window.show_all();
+  }
+  // SAFETY: the key is only ever set to a `()`.
+  unsafe { window.set_data(CONTENT_SHOWN_KEY, ()) };
+}
+
+/// Shows the window like [`show_content`], optionally without giving it focus.
+pub fn show_window<W: GtkWindowExt + WidgetExt + ContainerExt>(window: &W, focus: bool) {
+  // A window created hidden may come up at its 1x1 default size instead of the size requested
+  // while it was hidden, so request that size again once it's mapped for the first time.
+  let pending_size = (!window.is_realized()).then(|| window.size());
+
+  if !focus {
+    window.set_focus_on_map(false);
+  }
+
+  show_content(window);
+
+  if !focus {
+    window.set_focus_on_map(true);
+  }
+
+  if let Some((width, height)) = pending_size {
+    window.resize(width, height);
+  }
+}
+
+/// Resizes the window back into its size constraints when the window manager ignored the geometry
+/// hints set by [`set_size_constraints`].
+///
+/// `last_correction` holds the size requested by the previous correction, if the window manager
+/// answers it with the same violating size again we give up instead of resizing in a loop.
+pub fn enforce_size_constraints<W: GtkWindowExt + WidgetExt>(
+  window: &W,
+  (width, height): (i32, i32),
+  constraints: &WindowSizeConstraints,
+  last_correction: &Cell<Option<(i32, i32)>>,
+) {
+  if !constraints.has_min() && !constraints.has_max() {
+    return;
+  }
+
+  // maximized, fullscreen and tiled windows are sized by the window manager
+  let managed =
+    gdk::WindowState::MAXIMIZED | gdk::WindowState::FULLSCREEN | gdk::WindowState::TILED;
+  if window
+    .window()
+    .map(|w| w.state().intersects(managed))
+    .unwrap_or(false)
+  {
+    return;
+  }
+
+  let scale_factor = window.scale_factor() as f64;
+  let min_size: LogicalSize<i32> = constraints.min_size_logical(scale_factor);
+  let max_size: LogicalSize<i32> = constraints.max_size_logical(scale_factor);
+  let clamped = (
+    width.min(max_size.width).max(min_size.width),
+    height.min(max_size.height).max(min_size.height),
+  );
+
+  if clamped == (width, height) {
+    last_correction.set(None);
+  } else if last_correction.get() != Some(clamped) {
+    last_correction.set(Some(clamped));
+    window.resize(clamped.0, clamped.1);
+  }
+}
+
+const MINIMIZABLE_KEY: &str = "tao-minimizable";
+const MAXIMIZABLE_KEY: &str = "tao-maximizable";
+
+/// Sets whether the window manager offers to minimize `window`.
+pub fn set_minimizable<W: GtkWindowExt + WidgetExt>(window: &W, minimizable: bool) {
+  // SAFETY: the key is only ever set to a `bool`.
+  unsafe { window.set_data(MINIMIZABLE_KEY, minimizable) };
+  update_window_functions(window);
+}
+
+/// Sets whether the window manager offers to maximize `window`, which it only does for resizable
+/// windows.
+pub fn set_maximizable<W: GtkWindowExt + WidgetExt>(window: &W, maximizable: bool) {
+  // SAFETY: the key is only ever set to a `bool`.
+  unsafe { window.set_data(MAXIMIZABLE_KEY, maximizable) };
+  update_window_functions(window);
+}
+
+pub fn is_minimizable<W: GtkWindowExt>(window: &W) -> bool {
+  // SAFETY: the key is only ever set to a `bool`.
+  unsafe {
+    window
+      .data::<bool>(MINIMIZABLE_KEY)
+      .map_or(true, |v| *v.as_ref())
+  }
+}
+
+pub fn is_maximizable<W: GtkWindowExt>(window: &W) -> bool {
+  // SAFETY: the key is only ever set to a `bool`.
+  unsafe {
+    window
+      .data::<bool>(MAXIMIZABLE_KEY)
+      .map_or(true, |v| *v.as_ref())
+  }
+}
+
+/// Updates the window manager functions so its buttons follow the minimizable, maximizable,
+/// resizable and closable states, GTK only removes the resize grips of non resizable windows.
+pub fn update_window_functions<W: GtkWindowExt + WidgetExt>(window: &W) {
+  if let Some(gdk_window) = window.window() {
+    let mut functions = gdk::WMFunction::MOVE;
+    if is_minimizable(window) {
+      functions |= gdk::WMFunction::MINIMIZE;
+    }
+    if window.is_resizable() {
+      functions |= gdk::WMFunction::RESIZE;
+      if is_maximizable(window) {
+        functions |= gdk::WMFunction::MAXIMIZE;
+      }
+    }
+    if window.is_deletable() {
+      functions |= gdk::WMFunction::CLOSE;
+    }
+    gdk_window.set_functions(functions);
+  }
+}
+
 pub struct WindowMaximizeProcess<W: GtkWindowExt + WidgetExt> {
   window: W,
   resizable: bool,
@@ -93,6 +248,7 @@ impl<W: GtkWindowExt + WidgetExt> WindowMaximizeProcess<W> {
     match self.step {
       0 => {
         self.window.set_resizable(true);
+        update_window_functions(&self.window);
         self.step += 1;
         glib::ControlFlow::Continue
       }
@@ -103,6 +259,7 @@ impl<W: GtkWindowExt + WidgetExt> WindowMaximizeProcess<W> {
       }
       2 => {
         self.window.set_resizable(self.resizable);
+        update_window_functions(&self.window);
         glib::ControlFlow::Break
       }
       _ => glib::ControlFlow::Break,
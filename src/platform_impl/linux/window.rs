@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
-  cell::RefCell,
+  cell::{Cell, RefCell},
   collections::VecDeque,
   rc::Rc,
   sync::{
@@ -26,8 +26,8 @@ use crate::{
   monitor::MonitorHandle as RootMonitorHandle,
   platform_impl::wayland::header::WlHeader,
   window::{
-    CursorIcon, Fullscreen, ProgressBarState, ResizeDirection, Theme, UserAttentionType,
-    WindowAttributes, WindowSizeConstraints,
+    clamp_frame_to_work_areas, CursorIcon, Fullscreen, HitTestHandler, ProgressBarState,
+    ResizeDirection, Theme, UserAttentionType, WindowAttributes, WindowSizeConstraints,
   },
 };
 
@@ -50,6 +50,81 @@ impl WindowId {
 // ref: https://github.com/WebKit/WebKit/blob/e44ffaa0d999a9807f76f1805943eea204cfdfbc/Source/WebKit/UIProcess/API/gtk/PageClientImpl.cpp#L587
 const GTK_THEME_SUFFIX_LIST: [&'static str; 3] = ["-dark", "-Dark", "-Darker"];
 
+/// Resolves the theme currently applied by the GTK settings.
+/// Returns the size a new window is created with, the requested inner size (800x600 by default)
+/// clamped to the size constraints.
+fn initial_inner_size(attributes: &WindowAttributes, scale_factor: f64) -> LogicalSize<f64> {
+  let desired_size = attributes
+    .inner_size
+    .unwrap_or_else(|| LogicalSize::new(800, 600).into());
+  attributes
+    .inner_size_constraints
+    .clamp(desired_size, scale_factor)
+    .to_logical(scale_factor)
+}
+
+pub(crate) fn theme_from_settings(settings: &Settings) -> Theme {
+  if settings.is_gtk_application_prefer_dark_theme() {
+    return Theme::Dark;
+  }
+
+  if let Some(theme) = settings.gtk_theme_name() {
+    let theme = theme.as_str();
+    if GTK_THEME_SUFFIX_LIST.iter().any(|t| theme.ends_with(t)) {
+      return Theme::Dark;
+    }
+  }
+
+  Theme::Light
+}
+
+/// Calls `f` with the resolved theme every time the GTK theme settings change, until `window` is
+/// destroyed.
+pub(crate) fn connect_theme_changed<F: Fn(Theme) + 'static>(window: &impl IsA<gtk::Widget>, f: F) {
+  let settings = match Settings::default() {
+    Some(settings) => settings,
+    None => return,
+  };
+
+  let f = Rc::new(f);
+  let f_ = f.clone();
+  let prefer_dark_handler =
+    settings.connect_gtk_application_prefer_dark_theme_notify(move |settings| {
+      f_(theme_from_settings(settings))
+    });
+  let theme_name_handler =
+    settings.connect_gtk_theme_name_notify(move |settings| f(theme_from_settings(settings)));
+
+  let handlers = Cell::new(Some((prefer_dark_handler, theme_name_handler)));
+  window.connect_destroy(move |_| {
+    if let Some((prefer_dark_handler, theme_name_handler)) = handlers.take() {
+      settings.disconnect(prefer_dark_handler);
+      settings.disconnect(theme_name_handler);
+    }
+  });
+}
+
+/// Applies `theme` to the GTK settings.
+pub(crate) fn apply_theme(settings: &Settings, theme: Theme) {
+  match theme {
+    Theme::Dark => settings.set_gtk_application_prefer_dark_theme(true),
+    Theme::Light => {
+      settings.set_gtk_application_prefer_dark_theme(false);
+      if let Some(theme) = settings.gtk_theme_name() {
+        let theme = theme.as_str();
+        // Remove dark variant.
+        if let Some(theme) = GTK_THEME_SUFFIX_LIST
+          .iter()
+          .find(|t| theme.ends_with(*t))
+          .map(|v| theme.strip_suffix(v))
+        {
+          settings.set_gtk_theme_name(theme);
+        }
+      }
+    }
+  }
+}
+
 pub struct Window {
   /// Window id.
   pub(crate) window_id: WindowId,
@@ -65,10 +140,13 @@ pub struct Window {
   is_always_on_top: Rc<AtomicBool>,
   minimized: Rc<AtomicBool>,
   fullscreen: RefCell<Option<Fullscreen>>,
-  inner_size_constraints: RefCell<WindowSizeConstraints>,
+  window_icon: RefCell<Option<Icon>>,
+  inner_size_constraints: Rc<RefCell<WindowSizeConstraints>>,
   /// Draw event Sender
   draw_tx: crossbeam_channel::Sender<WindowId>,
-  preferred_theme: RefCell<Option<Theme>>,
+  /// Theme resolved from the GTK settings, updated by `set_theme` and before `ThemeChanged` is
+  /// emitted
+  current_theme: Rc<Cell<Theme>>,
 }
 
 impl Window {
@@ -86,7 +164,9 @@ impl Window {
       .application(app)
       .accept_focus(attributes.focused);
     if let Parent::ChildOf(parent) = pl_attribs.parent {
-      window_builder = window_builder.transient_for(&parent);
+      window_builder = window_builder
+        .transient_for(&parent)
+        .destroy_with_parent(true);
     }
 
     let window = window_builder.build();
@@ -103,10 +183,7 @@ impl Window {
 
     // Set Width/Height & Resizable
     let win_scale_factor = window.scale_factor();
-    let (width, height) = attributes
-      .inner_size
-      .map(|size| size.to_logical::<f64>(win_scale_factor as f64).into())
-      .unwrap_or((800, 600));
+    let (width, height) = initial_inner_size(&attributes, win_scale_factor as f64).into();
     window.set_default_size(1, 1);
     window.resize(width, height);
 
@@ -121,13 +198,37 @@ impl Window {
     }
 
     window.set_deletable(attributes.closable);
+    util::set_minimizable(&window, attributes.minimizable);
+    util::set_maximizable(&window, attributes.maximizable);
+    window.connect_realize(util::update_window_functions);
 
     // Set Min/Max Size
     util::set_size_constraints(&window, attributes.inner_size_constraints);
 
     // Set Position
     if let Some(position) = attributes.position {
-      let (x, y): (i32, i32) = position.to_logical::<i32>(win_scale_factor as f64).into();
+      let (mut x, mut y): (i32, i32) = position.to_logical::<i32>(win_scale_factor as f64).into();
+      if attributes.position_clamped && !is_wayland {
+        // The decorations of the window manager aren't known before the window is mapped, so
+        // the frame is that of the window itself.
+        let frame = (x as f64, y as f64, (x + width) as f64, (y + height) as f64);
+        let display = window.display();
+        let work_areas: Vec<_> = (0..display.n_monitors())
+          .filter_map(|number| display.monitor(number))
+          .map(|monitor| {
+            let area = monitor.workarea();
+            (
+              area.x() as f64,
+              area.y() as f64,
+              (area.x() + area.width()) as f64,
+              (area.y() + area.height()) as f64,
+            )
+          })
+          .collect();
+        if let Some((left, top)) = clamp_frame_to_work_areas(frame, &work_areas, 1.0) {
+          (x, y) = (left.round() as i32, top.round() as i32);
+        }
+      }
       window.move_(x, y);
     }
 
@@ -197,40 +298,42 @@ impl Window {
       window.stick();
     }
 
-    if let Some(icon) = attributes.window_icon {
+    if let Some(icon) = attributes.window_icon.clone() {
       window.set_icon(Some(&icon.inner.into()));
+    } else if event_loop_window_target.is_wayland() {
+      util::set_wayland_icon_name(
+        &window,
+        event_loop_window_target.app.application_id().as_deref(),
+      );
     }
 
-    let preferred_theme = if let Some(settings) = Settings::default() {
+    let theme = if let Some(settings) = Settings::default() {
       if let Some(preferred_theme) = attributes.preferred_theme {
-        match preferred_theme {
-          Theme::Dark => settings.set_gtk_application_prefer_dark_theme(true),
-          Theme::Light => {
-            if let Some(theme) = settings.gtk_theme_name() {
-              let theme = theme.as_str();
-              // Remove dark variant.
-              if let Some(theme) = GTK_THEME_SUFFIX_LIST
-                .iter()
-                .find(|t| theme.ends_with(*t))
-                .map(|v| theme.strip_suffix(v))
-              {
-                settings.set_gtk_theme_name(theme);
-              }
-            }
-          }
-        }
+        apply_theme(&settings, preferred_theme);
       }
-      attributes.preferred_theme
+      theme_from_settings(&settings)
     } else {
-      None
+      attributes.preferred_theme.unwrap_or_default()
     };
+    let current_theme = Rc::new(Cell::new(theme));
+    let current_theme_clone = current_theme.clone();
+    connect_theme_changed(&window, move |theme| current_theme_clone.set(theme));
 
     if attributes.visible {
-      window.show_all();
+      util::show_content(&window);
     } else {
       window.hide();
     }
 
+    if let Some(below) = attributes.below.filter(|_| attributes.visible) {
+      if let (Some(gdk_window), Some(sibling)) = (
+        window.window(),
+        app.window_by_id(below.0 .0).and_then(|w| w.window()),
+      ) {
+        gdk_window.restack(Some(&sibling), false);
+      }
+    }
+
     // restore accept-focus after the window has been drawn
     // if the window was initially created without focus
     if !attributes.focused {
@@ -254,7 +357,11 @@ impl Window {
     let size: Rc<(AtomicI32, AtomicI32)> = Rc::new((w_size.0.into(), w_size.1.into()));
     let size_clone = size.clone();
 
-    window.connect_configure_event(move |_, event| {
+    let inner_size_constraints = Rc::new(RefCell::new(attributes.inner_size_constraints));
+    let constraints_clone = inner_size_constraints.clone();
+    let last_correction = Cell::new(None);
+
+    window.connect_configure_event(move |window, event| {
       let (x, y) = event.position();
       position_clone.0.store(x, Ordering::Release);
       position_clone.1.store(y, Ordering::Release);
@@ -263,6 +370,13 @@ impl Window {
       size_clone.0.store(w as i32, Ordering::Release);
       size_clone.1.store(h as i32, Ordering::Release);
 
+      util::enforce_size_constraints(
+        window,
+        (w as i32, h as i32),
+        &constraints_clone.borrow(),
+        &last_correction,
+      );
+
       false
     });
 
@@ -309,6 +423,16 @@ impl Window {
       log::warn!("Failed to send redraw event to event channel: {}", e);
     }
 
+    // Covering the work area needs the window to be placed, which happens in the event loop
+    if let Some(fullscreen @ Fullscreen::BorderlessWorkArea(_)) = &attributes.fullscreen {
+      if let Err(e) = window_requests_tx.send((
+        window_id,
+        WindowRequest::Fullscreen(Some(fullscreen.clone())),
+      )) {
+        log::warn!("Fail to send fullscreen request: {}", e);
+      }
+    }
+
     let win = Self {
       window_id,
       window,
@@ -322,8 +446,9 @@ impl Window {
       minimized,
       is_always_on_top,
       fullscreen: RefCell::new(attributes.fullscreen),
-      inner_size_constraints: RefCell::new(attributes.inner_size_constraints),
-      preferred_theme: RefCell::new(preferred_theme),
+      window_icon: RefCell::new(attributes.window_icon),
+      inner_size_constraints,
+      current_theme,
     };
 
     win.set_skip_taskbar(pl_attribs.skip_taskbar);
@@ -354,7 +479,11 @@ impl Window {
     let size: Rc<(AtomicI32, AtomicI32)> = Rc::new((w_size.0.into(), w_size.1.into()));
     let size_clone = size.clone();
 
-    window.connect_configure_event(move |_, event| {
+    let inner_size_constraints = Rc::new(RefCell::new(WindowSizeConstraints::default()));
+    let constraints_clone = inner_size_constraints.clone();
+    let last_correction = Cell::new(None);
+
+    window.connect_configure_event(move |window, event| {
       let (x, y) = event.position();
       position_clone.0.store(x, Ordering::Release);
       position_clone.1.store(y, Ordering::Release);
@@ -363,6 +492,13 @@ impl Window {
       size_clone.0.store(w as i32, Ordering::Release);
       size_clone.1.store(h as i32, Ordering::Release);
 
+      util::enforce_size_constraints(
+        window,
+        (w as i32, h as i32),
+        &constraints_clone.borrow(),
+        &last_correction,
+      );
+
       false
     });
 
@@ -388,6 +524,14 @@ impl Window {
       scale_factor_clone.store(window.scale_factor(), Ordering::Release);
     });
 
+    let current_theme = Rc::new(Cell::new(
+      Settings::default()
+        .map(|settings| theme_from_settings(&settings))
+        .unwrap_or_default(),
+    ));
+    let current_theme_clone = current_theme.clone();
+    connect_theme_changed(&window, move |theme| current_theme_clone.set(theme));
+
     if let Err(e) = draw_tx.send(window_id) {
       log::warn!("Failed to send redraw event to event channel: {}", e);
     }
@@ -405,8 +549,9 @@ impl Window {
       minimized,
       is_always_on_top,
       fullscreen: RefCell::new(None),
-      inner_size_constraints: RefCell::new(WindowSizeConstraints::default()),
-      preferred_theme: RefCell::new(None),
+      window_icon: RefCell::new(None),
+      inner_size_constraints,
+      current_theme,
     };
 
     Ok(win)
@@ -543,6 +688,15 @@ impl Window {
     }
   }
 
+  pub fn show_without_focus(&self) {
+    if let Err(e) = self
+      .window_requests_tx
+      .send((self.window_id, WindowRequest::ShowWithoutFocus))
+    {
+      log::warn!("Fail to send show without focus request: {}", e);
+    }
+  }
+
   pub fn set_focus(&self) {
     if !self.minimized.load(Ordering::Acquire) && self.window.get_visible() {
       if let Err(e) = self
@@ -554,10 +708,36 @@ impl Window {
     }
   }
 
+  pub fn activate(&self) {
+    if let Err(e) = self
+      .window_requests_tx
+      .send((self.window_id, WindowRequest::Activate))
+    {
+      log::warn!("Fail to send activate request: {}", e);
+    }
+  }
+
   pub fn is_focused(&self) -> bool {
     self.window.is_active()
   }
 
+  pub fn owner(&self) -> Option<WindowId> {
+    self
+      .window
+      .transient_for()
+      .and_then(|owner| owner.downcast::<gtk::ApplicationWindow>().ok())
+      .map(|owner| WindowId(owner.id()))
+  }
+
+  pub fn owned_windows(&self) -> Vec<WindowId> {
+    gtk::Window::list_toplevels()
+      .into_iter()
+      .filter_map(|window| window.downcast::<gtk::ApplicationWindow>().ok())
+      .filter(|window| window.transient_for().as_ref() == Some(self.window.upcast_ref()))
+      .map(|window| WindowId(window.id()))
+      .collect()
+  }
+
   pub fn set_resizable(&self, resizable: bool) {
     if let Err(e) = self
       .window_requests_tx
@@ -567,9 +747,23 @@ impl Window {
     }
   }
 
-  pub fn set_minimizable(&self, _minimizable: bool) {}
+  pub fn set_minimizable(&self, minimizable: bool) {
+    if let Err(e) = self
+      .window_requests_tx
+      .send((self.window_id, WindowRequest::Minimizable(minimizable)))
+    {
+      log::warn!("Fail to send minimizable request: {}", e);
+    }
+  }
 
-  pub fn set_maximizable(&self, _maximizable: bool) {}
+  pub fn set_maximizable(&self, maximizable: bool) {
+    if let Err(e) = self
+      .window_requests_tx
+      .send((self.window_id, WindowRequest::Maximizable(maximizable)))
+    {
+      log::warn!("Fail to send maximizable request: {}", e);
+    }
+  }
 
   pub fn set_closable(&self, closable: bool) {
     if let Err(e) = self
@@ -617,11 +811,11 @@ impl Window {
   }
 
   pub fn is_minimizable(&self) -> bool {
-    true
+    util::is_minimizable(&self.window)
   }
 
   pub fn is_maximizable(&self) -> bool {
-    true
+    util::is_maximizable(&self.window)
   }
   pub fn is_closable(&self) -> bool {
     self.window.is_deletable()
@@ -698,6 +892,7 @@ impl Window {
   }
 
   pub fn set_window_icon(&self, window_icon: Option<Icon>) {
+    self.window_icon.replace(window_icon.clone());
     if let Err(e) = self
       .window_requests_tx
       .send((self.window_id, WindowRequest::WindowIcon(window_icon)))
@@ -706,10 +901,23 @@ impl Window {
     }
   }
 
+  pub fn window_icon(&self) -> Option<Icon> {
+    self.window_icon.borrow().clone()
+  }
+
   pub fn set_ime_position<P: Into<Position>>(&self, _position: P) {
     //TODO
   }
 
+  pub fn set_ime_allowed(&self, allowed: bool) {
+    if let Err(e) = self
+      .window_requests_tx
+      .send((self.window_id, WindowRequest::ImeAllowed(allowed)))
+    {
+      log::warn!("Fail to send ime allowed request: {}", e);
+    }
+  }
+
   pub fn request_user_attention(&self, request_type: Option<UserAttentionType>) {
     if let Err(e) = self
       .window_requests_tx
@@ -736,17 +944,64 @@ impl Window {
     }
   }
 
+  pub fn set_drag_region(&self, region: Option<(Position, Size)>) {
+    let scale_factor = self.scale_factor();
+    let region = region.map(|(position, size)| {
+      (
+        position.to_logical(scale_factor),
+        size.to_logical(scale_factor),
+      )
+    });
+    if let Err(e) = self
+      .window_requests_tx
+      .send((self.window_id, WindowRequest::DragRegion(region)))
+    {
+      log::warn!("Fail to send drag region request: {}", e);
+    }
+  }
+
+  pub fn set_resize_border_thickness(&self, thickness: Option<f64>) {
+    if let Err(e) = self.window_requests_tx.send((
+      self.window_id,
+      WindowRequest::ResizeBorderThickness(thickness),
+    )) {
+      log::warn!("Fail to send resize border thickness request: {}", e);
+    }
+  }
+
+  pub fn set_hit_test_handler(&self, handler: Option<HitTestHandler>) {
+    if let Err(e) = self
+      .window_requests_tx
+      .send((self.window_id, WindowRequest::HitTestHandler(handler)))
+    {
+      log::warn!("Fail to send hit test handler request: {}", e);
+    }
+  }
+
   pub fn set_cursor_position<P: Into<Position>>(&self, position: P) -> Result<(), ExternalError> {
-    let inner_pos = self.inner_position().unwrap_or_default();
+    // `self.position` is already logical, like the coordinates the pointer is warped to
+    let (inner_x, inner_y) = &*self.position;
     let (x, y): (i32, i32) = position
       .into()
       .to_logical::<i32>(self.scale_factor())
       .into();
 
-    if let Err(e) = self.window_requests_tx.send((
-      self.window_id,
-      WindowRequest::CursorPosition((x + inner_pos.x, y + inner_pos.y)),
-    )) {
+    self.set_cursor_position_screen(
+      LogicalPosition::new(
+        x + inner_x.load(Ordering::Acquire),
+        y + inner_y.load(Ordering::Acquire),
+      )
+      .into(),
+    )
+  }
+
+  pub fn set_cursor_position_screen(&self, position: Position) -> Result<(), ExternalError> {
+    let (x, y): (i32, i32) = position.to_logical::<i32>(self.scale_factor()).into();
+
+    if let Err(e) = self
+      .window_requests_tx
+      .send((self.window_id, WindowRequest::CursorPosition((x, y))))
+    {
       log::warn!("Fail to send cursor position request: {}", e);
     }
 
@@ -967,22 +1222,14 @@ impl Window {
   }
 
   pub fn theme(&self) -> Theme {
-    if let Some(theme) = *self.preferred_theme.borrow() {
-      return theme;
-    }
-
-    if let Some(theme) = Settings::default().and_then(|s| s.gtk_theme_name()) {
-      let theme = theme.as_str();
-      if GTK_THEME_SUFFIX_LIST.iter().any(|t| theme.ends_with(t)) {
-        return Theme::Dark;
-      }
-    }
-
-    Theme::Light
+    self.current_theme.get()
   }
 
   pub fn set_theme(&self, theme: Option<Theme>) {
-    *self.preferred_theme.borrow_mut() = theme;
+    // The settings are applied on the event loop, update the getter right away.
+    if let Some(theme) = theme {
+      self.current_theme.set(theme);
+    }
     if let Err(e) = self
       .window_requests_tx
       .send((WindowId::dummy(), WindowRequest::SetTheme(theme)))
@@ -1004,8 +1251,12 @@ pub enum WindowRequest {
   Size((i32, i32)),
   SizeConstraints(WindowSizeConstraints),
   Visible(bool),
+  ShowWithoutFocus,
   Focus,
+  Activate,
   Resizable(bool),
+  Minimizable(bool),
+  Maximizable(bool),
   Closable(bool),
   Minimized(bool),
   Maximized(bool, bool),
@@ -1021,6 +1272,7 @@ pub enum WindowRequest {
   CursorIcon(Option<CursorIcon>),
   CursorPosition((i32, i32)),
   CursorIgnoreEvents(bool),
+  ImeAllowed(bool),
   WireUpEvents {
     transparent: bool,
     fullscreen: bool,
@@ -1029,6 +1281,10 @@ pub enum WindowRequest {
   SetVisibleOnAllWorkspaces(bool),
   ProgressBarState(ProgressBarState),
   SetTheme(Option<Theme>),
+  BusyCursor(bool),
+  DragRegion(Option<(LogicalPosition<f64>, LogicalSize<f64>)>),
+  ResizeBorderThickness(Option<f64>),
+  HitTestHandler(Option<HitTestHandler>),
 }
 
 impl Drop for Window {
@@ -1038,3 +1294,33 @@ impl Drop for Window {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dpi::{LogicalUnit, PhysicalUnit};
+
+  #[test]
+  fn initial_size_is_clamped_to_max() {
+    let mut attributes = WindowAttributes {
+      inner_size: Some(PhysicalSize::new(2000, 2000).into()),
+      ..Default::default()
+    };
+    attributes.inner_size_constraints.max_width = Some(PhysicalUnit::new(800).into());
+    attributes.inner_size_constraints.max_height = Some(PhysicalUnit::new(600).into());
+
+    let size: PhysicalSize<u32> = initial_inner_size(&attributes, 2.0).to_physical(2.0);
+    assert_eq!(size, PhysicalSize::new(800, 600));
+  }
+
+  #[test]
+  fn default_initial_size_is_clamped_to_min() {
+    let mut attributes = WindowAttributes::default();
+    attributes.inner_size_constraints.min_width = Some(LogicalUnit::new(1000.0).into());
+
+    assert_eq!(
+      initial_inner_size(&attributes, 1.5),
+      LogicalSize::new(1000.0, 600.0)
+    );
+  }
+}
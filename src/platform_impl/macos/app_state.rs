@@ -131,6 +131,7 @@ struct Handler {
   callback: Mutex<Option<Box<dyn EventHandler>>>,
   pending_events: Mutex<VecDeque<EventWrapper>>,
   pending_redraw: Mutex<Vec<WindowId>>,
+  pending_activation: Mutex<Vec<WindowId>>,
   waker: Mutex<EventLoopWaker>,
 }
 
@@ -146,6 +147,10 @@ impl Handler {
     self.pending_redraw.lock().unwrap()
   }
 
+  fn activations(&self) -> MutexGuard<'_, Vec<WindowId>> {
+    self.pending_activation.lock().unwrap()
+  }
+
   fn waker(&self) -> MutexGuard<'_, EventLoopWaker> {
     self.waker.lock().unwrap()
   }
@@ -238,9 +243,18 @@ impl Handler {
     callback.handle_nonuser_event(event, &mut *self.control_flow.lock().unwrap());
 
     if old_size != size {
+      // `windowDidResize:` emits the `Resized` event
       let logical_size = size.to_logical(scale_factor);
       let size = NSSize::new(logical_size.width, logical_size.height);
       unsafe { NSWindow::setContentSize_(*ns_window, size) };
+    } else {
+      // The logical size is usually unchanged, so `windowDidResize:` isn't triggered even though
+      // the physical size changed.
+      let event = Event::WindowEvent {
+        window_id: WindowId(get_window_id(*ns_window)),
+        event: WindowEvent::Resized(size),
+      };
+      callback.handle_nonuser_event(event, &mut self.control_flow.lock().unwrap());
     }
   }
 
@@ -270,6 +284,22 @@ impl AppState {
     }));
   }
 
+  #[cfg(feature = "test-util")]
+  pub fn is_launched() -> bool {
+    HANDLER.is_ready()
+  }
+
+  #[cfg(feature = "test-util")]
+  pub fn should_exit() -> bool {
+    HANDLER.should_exit()
+  }
+
+  /// Drops the callback set with [`AppState::set_callback`] without sending `LoopDestroyed`.
+  #[cfg(feature = "test-util")]
+  pub fn clear_callback() {
+    HANDLER.callback.lock().unwrap().take();
+  }
+
   pub fn exit() -> i32 {
     HANDLER.set_in_callback(true);
     HANDLER.handle_nonuser_event(EventWrapper::StaticEvent(Event::LoopDestroyed));
@@ -360,6 +390,14 @@ impl AppState {
     }
   }
 
+  /// Records a window made key before the application finished launching, so that
+  /// `window_activation_hack` only activates the windows created focused.
+  pub fn queue_activation(window_id: WindowId) {
+    if !HANDLER.is_ready() {
+      HANDLER.activations().push(window_id);
+    }
+  }
+
   pub fn handle_redraw(window_id: WindowId) {
     HANDLER.handle_nonuser_event(EventWrapper::StaticEvent(Event::RedrawRequested(window_id)));
   }
@@ -429,6 +467,7 @@ impl AppState {
 unsafe fn window_activation_hack(ns_app: id) {
   // Get the application's windows
   // TODO: Proper ordering of the windows
+  let pending_activation = mem::take(&mut *HANDLER.activations());
   let ns_windows: id = msg_send![ns_app, windows];
   let ns_enumerator: id = msg_send![ns_windows, objectEnumerator];
   loop {
@@ -438,13 +477,14 @@ unsafe fn window_activation_hack(ns_app: id) {
       break;
     }
     // And call `makeKeyAndOrderFront` if it was called on the window in `UnownedWindow::new`
-    // This way we preserve the user's desired initial visiblity status
+    // This way we preserve the user's desired initial visiblity and focus status
     // TODO: Also filter on the type/"level" of the window, and maybe other things?
-    if ns_window.isVisible() == YES {
+    let window_id = WindowId(get_window_id(ns_window));
+    if ns_window.isVisible() == YES && pending_activation.contains(&window_id) {
       trace!("Activating visible window");
       ns_window.makeKeyAndOrderFront_(nil);
     } else {
-      trace!("Skipping activating invisible window");
+      trace!("Skipping activating invisible or unfocused window");
     }
   }
 }
@@ -42,7 +42,18 @@ use crate::{
   window::{ProgressBarState, Theme},
 };
 
-use super::window::set_ns_theme;
+use super::window::{get_ns_theme, set_ns_theme};
+
+#[cfg(feature = "test-util")]
+use cocoa::{
+  appkit::NSEventMask,
+  foundation::{NSDate, NSDefaultRunLoopMode},
+};
+#[cfg(feature = "test-util")]
+use std::time::Duration;
+
+#[cfg(feature = "test-util")]
+use crate::platform::pump_events::PumpStatus;
 
 #[derive(Default)]
 pub struct PanicInfo {
@@ -102,6 +113,23 @@ impl<T: 'static> EventLoopWindowTarget<T> {
     Some(RootMonitorHandle { inner: monitor })
   }
 
+  #[inline]
+  pub fn current_monitor(&self) -> Option<RootMonitorHandle> {
+    let screen = unsafe {
+      let key_window: id = msg_send![NSApp(), keyWindow];
+      if key_window == nil {
+        nil
+      } else {
+        msg_send![key_window, screen]
+      }
+    };
+    if screen == nil {
+      return self.primary_monitor();
+    }
+    let monitor = unsafe { monitor::from_ns_screen(screen) };
+    Some(RootMonitorHandle { inner: monitor })
+  }
+
   #[cfg(feature = "rwh_05")]
   #[inline]
   pub fn raw_display_handle_rwh_05(&self) -> rwh_05::RawDisplayHandle {
@@ -125,6 +153,16 @@ impl<T: 'static> EventLoopWindowTarget<T> {
     set_progress_indicator(progress);
   }
 
+  #[inline]
+  pub fn set_busy_cursor(&self, busy: bool) {
+    unsafe { util::set_busy(busy) }
+  }
+
+  #[inline]
+  pub fn theme(&self) -> Theme {
+    get_ns_theme()
+  }
+
   #[inline]
   pub fn set_theme(&self, theme: Option<Theme>) {
     set_ns_theme(theme)
@@ -176,6 +214,11 @@ impl<T> EventLoop<T> {
       delegate,
       window_target: Rc::new(RootWindowTarget {
         p: Default::default(),
+        control_flow: Default::default(),
+        busy_cursor_depth: Default::default(),
+        resize_debouncer: Default::default(),
+        #[cfg(feature = "trace")]
+        stats: Default::default(),
         _marker: PhantomData,
       }),
       panic_info,
@@ -237,6 +280,86 @@ impl<T> EventLoop<T> {
     exit_code
   }
 
+  /// Sends the events that are already queued, or that arrive within `timeout`, without running
+  /// `NSApp`.
+  #[cfg(feature = "test-util")]
+  pub fn pump_events<F>(&mut self, timeout: Option<Duration>, callback: F) -> PumpStatus
+  where
+    F: FnMut(Event<'_, T>, &RootWindowTarget<T>, &mut ControlFlow),
+  {
+    // Same as in `run_return`, the callback only has to outlive this call since it is dropped
+    // before returning.
+    let callback = unsafe {
+      mem::transmute::<
+        Rc<RefCell<dyn FnMut(Event<'_, T>, &RootWindowTarget<T>, &mut ControlFlow)>>,
+        Rc<RefCell<dyn FnMut(Event<'_, T>, &RootWindowTarget<T>, &mut ControlFlow)>>,
+      >(Rc::new(RefCell::new(callback)))
+    };
+
+    self._callback = Some(Rc::clone(&callback));
+
+    let exit_code = unsafe {
+      let pool = NSAutoreleasePool::new(nil);
+      defer!(pool.drain());
+      let app = NSApp();
+      assert_ne!(app, nil);
+
+      let weak_cb: Weak<_> = Rc::downgrade(&callback);
+      mem::drop(callback);
+
+      AppState::set_callback(weak_cb, Rc::clone(&self.window_target));
+
+      // Sends `StartCause::Init` from `applicationDidFinishLaunching:`.
+      if !AppState::is_launched() {
+        let () = msg_send![app, finishLaunching];
+      }
+
+      // Only the first event is waited for, the rest is drained from what is already queued.
+      let mut until: id = match timeout {
+        Some(timeout) => {
+          msg_send![class!(NSDate), dateWithTimeIntervalSinceNow: timeout.as_secs_f64()]
+        }
+        None => NSDate::distantFuture(nil),
+      };
+      loop {
+        let event: id = msg_send![
+          app,
+          nextEventMatchingMask: NSEventMask::NSAnyEventMask.bits()
+          untilDate: until
+          inMode: NSDefaultRunLoopMode
+          dequeue: YES
+        ];
+        if event == nil {
+          break;
+        }
+        let () = msg_send![app, sendEvent: event];
+        until = NSDate::distantPast(nil);
+      }
+
+      // The run loop observer only clears the events when it is about to sleep, which doesn't
+      // happen while draining.
+      AppState::cleared(Rc::downgrade(&self.panic_info));
+
+      if let Some(panic) = self.panic_info.take() {
+        drop(self._callback.take());
+        resume_unwind(panic);
+      }
+
+      if AppState::should_exit() {
+        Some(AppState::exit())
+      } else {
+        AppState::clear_callback();
+        None
+      }
+    };
+    drop(self._callback.take());
+
+    match exit_code {
+      Some(code) => PumpStatus::Exit(code),
+      None => PumpStatus::Continue,
+    }
+  }
+
   pub fn create_proxy(&self) -> Proxy<T> {
     Proxy::new(self.window_target.p.sender.clone())
   }
@@ -10,12 +10,12 @@ use super::{
 };
 use crate::{
   dpi::{PhysicalPosition, PhysicalSize},
-  monitor::{MonitorHandle as RootMonitorHandle, VideoMode as RootVideoMode},
+  monitor::{HdrMetadata, MonitorHandle as RootMonitorHandle, VideoMode as RootVideoMode},
 };
 use cocoa::{
-  appkit::{CGPoint, NSScreen},
+  appkit::{CGFloat, CGPoint, NSScreen},
   base::{id, nil},
-  foundation::NSUInteger,
+  foundation::{NSDictionary, NSUInteger},
 };
 use core_foundation::{
   array::{CFArrayGetCount, CFArrayGetValueAtIndex},
@@ -23,6 +23,7 @@ use core_foundation::{
   string::CFString,
 };
 use core_graphics::display::{CGDirectDisplayID, CGDisplay, CGDisplayBounds};
+use objc::runtime::{BOOL, NO};
 
 #[derive(Clone)]
 pub struct VideoMode {
@@ -174,6 +175,15 @@ pub fn from_point(x: f64, y: f64) -> Option<MonitorHandle> {
   return None;
 }
 
+// `from_ns_screen` get the monitor handle of an `NSScreen`.
+pub unsafe fn from_ns_screen(screen: id) -> MonitorHandle {
+  let desc = NSScreen::deviceDescription(screen);
+  let key = util::ns_string_id_ref("NSScreenNumber");
+  let value = NSDictionary::valueForKey_(desc, *key);
+  let display_id: NSUInteger = msg_send![value, unsignedIntegerValue];
+  MonitorHandle::new(display_id.try_into().unwrap())
+}
+
 impl fmt::Debug for MonitorHandle {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     // TODO: Do this using the proper fmt API
@@ -313,6 +323,29 @@ impl MonitorHandle {
     }
   }
 
+  pub fn hdr_metadata(&self) -> Option<HdrMetadata> {
+    let screen = self.ns_screen()?;
+    unsafe {
+      // available since macOS 10.15
+      let has_edr: BOOL = msg_send![
+        screen,
+        respondsToSelector: sel!(maximumPotentialExtendedDynamicRangeColorComponentValue)
+      ];
+      if has_edr == NO {
+        return None;
+      }
+      let max_edr: CGFloat = msg_send![
+        screen,
+        maximumPotentialExtendedDynamicRangeColorComponentValue
+      ];
+      Some(HdrMetadata {
+        supports_hdr: max_edr > 1.0,
+        max_luminance: None,
+        min_luminance: None,
+      })
+    }
+  }
+
   pub(crate) fn ns_screen(&self) -> Option<id> {
     unsafe {
       let uuid = ffi::CGDisplayCreateUUIDFromDisplayID(self.0);
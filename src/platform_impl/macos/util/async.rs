@@ -10,7 +10,7 @@ use std::{
 use cocoa::{
   appkit::{CGFloat, NSScreen, NSWindow, NSWindowStyleMask},
   base::{id, nil},
-  foundation::{NSPoint, NSSize, NSString},
+  foundation::{NSArray, NSPoint, NSSize, NSString},
 };
 use dispatch::Queue;
 use objc::{
@@ -144,11 +144,21 @@ pub unsafe fn toggle_full_screen_async(
   });
 }
 
+unsafe fn restore_display_mode(ns_screen: u32) {
+  ffi::CGRestorePermanentDisplayConfiguration();
+  let result = ffi::CGDisplayRelease(ns_screen);
+  if result != ffi::kCGErrorSuccess {
+    warn!("Failed to release display {}: error {}", ns_screen, result);
+  }
+}
+
 pub unsafe fn restore_display_mode_async(ns_screen: u32) {
-  Queue::main().exec_async(move || {
-    ffi::CGRestorePermanentDisplayConfiguration();
-    assert_eq!(ffi::CGDisplayRelease(ns_screen), ffi::kCGErrorSuccess);
-  });
+  Queue::main().exec_async(move || restore_display_mode(ns_screen));
+}
+
+// Restores the display before returning, so that a video mode set right after isn't reverted
+pub unsafe fn restore_display_mode_sync(ns_screen: u32) {
+  run_on_main(move || restore_display_mode(ns_screen));
 }
 
 // `setMaximized` is not thread-safe
@@ -236,6 +246,20 @@ pub unsafe fn set_focus(ns_window: id) {
   });
 }
 
+// `deminiaturize:` and `makeKeyAndOrderFront:` aren't thread-safe.
+pub unsafe fn activate(ns_window: id) {
+  let ns_window = MainThreadSafe(ns_window);
+  run_on_main(move || {
+    let is_minimized: BOOL = msg_send![*ns_window, isMiniaturized];
+    if is_minimized == YES {
+      ns_window.deminiaturize_(nil);
+    }
+    ns_window.makeKeyAndOrderFront_(nil);
+    let app: id = msg_send![class!(NSApplication), sharedApplication];
+    let () = msg_send![app, activateIgnoringOtherApps: YES];
+  });
+}
+
 // `close:` is thread-safe, but we want the event to be triggered from the main
 // thread. Though, it's a good idea to look into that more...
 //
@@ -245,11 +269,23 @@ pub unsafe fn close_async(ns_window: IdRef) {
   let ns_window = MainThreadSafe(ns_window);
   run_on_main(move || {
     autoreleasepool(move || {
-      ns_window.close();
+      close_with_child_windows(**ns_window);
     });
   });
 }
 
+// Child windows outlive their parent on macOS, close them first like owned windows are on the
+// other platforms. Closing them again when their own `Window` is dropped is a no-op.
+unsafe fn close_with_child_windows(ns_window: id) {
+  let child_windows: id = msg_send![ns_window, childWindows];
+  if child_windows != nil {
+    for i in 0..child_windows.count() {
+      close_with_child_windows(child_windows.objectAtIndex(i));
+    }
+  }
+  ns_window.close();
+}
+
 // `setIgnoresMouseEvents_:` isn't thread-safe, and fails silently.
 pub unsafe fn set_ignore_mouse_events(ns_window: id, ignore: bool) {
   let ns_window = MainThreadSafe(ns_window);
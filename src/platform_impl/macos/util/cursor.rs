@@ -3,12 +3,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use cocoa::{
-  appkit::NSImage,
+  appkit::{NSApp, NSImage},
   base::{id, nil},
-  foundation::{NSDictionary, NSPoint, NSString},
+  foundation::{NSArray, NSDictionary, NSPoint, NSString},
 };
 use objc::runtime::{Sel, NO};
-use std::{cell::RefCell, ptr::null_mut};
+use std::{
+  cell::{Cell, RefCell},
+  ptr::null_mut,
+};
 
 use crate::window::CursorIcon;
 
@@ -165,3 +168,37 @@ pub unsafe fn invisible_cursor() -> id {
     *cursor_obj.borrow()
   })
 }
+
+thread_local! {
+  static BUSY: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Whether the busy cursor is shown over every view, in place of their own cursor.
+pub fn is_busy() -> bool {
+  BUSY.with(Cell::get)
+}
+
+/// Shows or hides the busy cursor over all the windows of the application.
+pub unsafe fn set_busy(busy: bool) {
+  let was_busy = BUSY.with(|b| b.replace(busy));
+
+  // Pushing makes the cursor current right away, the cursor rects keep it while the mouse moves.
+  // Only pop what was pushed here to keep the cursor stack balanced.
+  match (was_busy, busy) {
+    (false, true) => {
+      let cursor = Cursor::from(CursorIcon::Wait).load();
+      let _: () = msg_send![cursor, push];
+    }
+    (true, false) => {
+      let _: () = msg_send![class!(NSCursor), pop];
+    }
+    _ => (),
+  }
+
+  let windows: id = msg_send![NSApp(), windows];
+  for i in 0..NSArray::count(windows) {
+    let window = windows.objectAtIndex(i);
+    let view: id = msg_send![window, contentView];
+    let _: () = msg_send![window, invalidateCursorRectsForView: view];
+  }
+}
@@ -21,7 +21,7 @@ use objc::{
 };
 
 use crate::{
-  dpi::LogicalPosition,
+  dpi::{LogicalPosition, LogicalSize},
   event::{
     DeviceEvent, ElementState, Event, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent,
   },
@@ -34,7 +34,7 @@ use crate::{
     window::get_window_id,
     DEVICE_ID,
   },
-  window::WindowId,
+  window::{CursorIcon, WindowId},
 };
 
 pub struct CursorState {
@@ -54,6 +54,7 @@ impl Default for CursorState {
 pub(super) struct ViewState {
   ns_window: id,
   pub cursor_state: Arc<Mutex<CursorState>>,
+  /// The IME position in logical pixels, relative to the top-left corner of the content view.
   ime_spot: Option<(f64, f64)>,
 
   /// This is true when we are currently modifying a marked text
@@ -71,6 +72,7 @@ pub(super) struct ViewState {
   phys_modifiers: HashSet<KeyCode>,
   tracking_rect: Option<NSInteger>,
   pub(super) traffic_light_inset: Option<LogicalPosition<f64>>,
+  drag_region: Option<(LogicalPosition<f64>, LogicalSize<f64>)>,
 }
 
 impl ViewState {
@@ -93,6 +95,7 @@ pub fn new_view(ns_window: id) -> (IdRef, Weak<Mutex<CursorState>>) {
     phys_modifiers: Default::default(),
     tracking_rect: None,
     traffic_light_inset: None,
+    drag_region: None,
   };
   unsafe {
     // This is free'd in `dealloc`
@@ -108,14 +111,36 @@ pub fn new_view(ns_window: id) -> (IdRef, Weak<Mutex<CursorState>>) {
 pub unsafe fn set_ime_position(ns_view: id, input_context: id, x: f64, y: f64) {
   let state_ptr: *mut c_void = *(*ns_view).get_mut_ivar("taoState");
   let state = &mut *(state_ptr as *mut ViewState);
-  let content_rect =
-    NSWindow::contentRectForFrameRect_(state.ns_window, NSWindow::frame(state.ns_window));
-  let base_x = content_rect.origin.x as f64;
-  let base_y = (content_rect.origin.y + content_rect.size.height) as f64;
-  state.ime_spot = Some((base_x + x, base_y - y));
+  state.ime_spot = Some((x, y));
   let _: () = msg_send![input_context, invalidateCharacterCoordinates];
 }
 
+pub unsafe fn set_drag_region(
+  ns_view: id,
+  region: Option<(LogicalPosition<f64>, LogicalSize<f64>)>,
+) {
+  let state_ptr: *mut c_void = *(*ns_view).get_mut_ivar("taoState");
+  let state = &mut *(state_ptr as *mut ViewState);
+  state.drag_region = region;
+}
+
+/// Whether `event` happened in the drag region of the view.
+unsafe fn in_drag_region(this: &Object, event: id) -> bool {
+  let state_ptr: *mut c_void = *this.get_ivar("taoState");
+  let state = &*(state_ptr as *const ViewState);
+  let (position, size) = match state.drag_region {
+    Some(region) => region,
+    None => return false,
+  };
+
+  let view: id = this as *const _ as *mut _;
+  let view_point = view.convertPoint_fromView_(event.locationInWindow(), nil);
+  let view_rect = NSView::frame(view);
+  let x = view_point.x as f64;
+  let y = view_rect.size.height as f64 - view_point.y as f64;
+  x >= position.x && x < position.x + size.width && y >= position.y && y < position.y + size.height
+}
+
 fn is_arrow_key(keycode: KeyCode) -> bool {
   matches!(
     keycode,
@@ -281,6 +306,10 @@ lazy_static! {
       sel!(pressureChangeWithEvent:),
       pressure_change_with_event as extern "C" fn(&Object, Sel, id),
     );
+    decl.add_method(
+      sel!(swipeWithEvent:),
+      swipe_with_event as extern "C" fn(&Object, Sel, id),
+    );
     decl.add_method(
       sel!(_wantsKeyDownForEvent:),
       wants_key_down_for_event as extern "C" fn(&Object, Sel, id) -> BOOL,
@@ -416,7 +445,9 @@ extern "C" fn reset_cursor_rects(this: &Object, _sel: Sel) {
 
     let bounds: NSRect = msg_send![this, bounds];
     let cursor_state = state.cursor_state.lock().unwrap();
-    let cursor = if cursor_state.visible {
+    let cursor = if util::is_busy() {
+      util::Cursor::from(CursorIcon::Wait).load()
+    } else if cursor_state.visible {
       cursor_state.cursor.load()
     } else {
       util::invisible_cursor()
@@ -531,15 +562,18 @@ extern "C" fn first_rect_for_character_range(
     trace!("Triggered `firstRectForCharacterRange`");
     let state_ptr: *mut c_void = *this.get_ivar("taoState");
     let state = &mut *(state_ptr as *mut ViewState);
-    let (x, y) = state.ime_spot.unwrap_or_else(|| {
-      let content_rect =
-        NSWindow::contentRectForFrameRect_(state.ns_window, NSWindow::frame(state.ns_window));
-      let x = content_rect.origin.x;
-      let y = util::bottom_left_to_top_left(content_rect);
-      (x, y)
-    });
+    // Resolved against the current frame so the candidate window follows the window when it
+    // moves. The returned rect is in screen coordinates, with a bottom-left origin.
+    let (x, y) = state.ime_spot.unwrap_or((0.0, 0.0));
+    let content_rect =
+      NSWindow::contentRectForFrameRect_(state.ns_window, NSWindow::frame(state.ns_window));
+    let base_x = content_rect.origin.x;
+    let base_y = content_rect.origin.y + content_rect.size.height;
     trace!("Completed `firstRectForCharacterRange`");
-    NSRect::new(NSPoint::new(x as _, y as _), NSSize::new(0.0, 0.0))
+    NSRect::new(
+      NSPoint::new((base_x + x) as _, (base_y - y) as _),
+      NSSize::new(0.0, 0.0),
+    )
   }
 }
 
@@ -973,6 +1007,14 @@ fn mouse_click(this: &Object, event: id, button: MouseButton, button_state: Elem
 
 extern "C" fn mouse_down(this: &Object, _sel: Sel, event: id) {
   mouse_motion(this, event);
+  unsafe {
+    if in_drag_region(this, event) {
+      let state_ptr: *mut c_void = *this.get_ivar("taoState");
+      let state = &*(state_ptr as *const ViewState);
+      let () = msg_send![state.ns_window, performWindowDragWithEvent: event];
+      return;
+    }
+  }
   mouse_click(this, event, MouseButton::Left, ElementState::Pressed);
 }
 
@@ -1173,6 +1215,37 @@ extern "C" fn pressure_change_with_event(this: &Object, _sel: Sel, event: id) {
   trace!("Completed `pressureChangeWithEvent`");
 }
 
+extern "C" fn swipe_with_event(this: &Object, _sel: Sel, event: id) {
+  trace!("Triggered `swipeWithEvent`");
+
+  unsafe {
+    let state_ptr: *mut c_void = *this.get_ivar("taoState");
+    let state = &mut *(state_ptr as *mut ViewState);
+
+    // AppKit reports a swipe to the left or upwards as a positive delta, the inverse of tao.
+    let delta = (-event.deltaX(), -event.deltaY());
+    let phase = match event.phase() {
+      NSEventPhase::NSEventPhaseMayBegin | NSEventPhase::NSEventPhaseBegan => TouchPhase::Started,
+      NSEventPhase::NSEventPhaseCancelled => TouchPhase::Cancelled,
+      NSEventPhase::NSEventPhaseChanged => TouchPhase::Moved,
+      // Swipes that aren't tracked are delivered as a single event without a phase.
+      _ => TouchPhase::Ended,
+    };
+
+    let window_event = Event::WindowEvent {
+      window_id: WindowId(get_window_id(state.ns_window)),
+      event: WindowEvent::TouchpadSwipe {
+        device_id: DEVICE_ID,
+        delta,
+        phase,
+      },
+    };
+
+    AppState::queue_event(EventWrapper::StaticEvent(window_event));
+  }
+  trace!("Completed `swipeWithEvent`");
+}
+
 // Allows us to receive Ctrl-Tab and Ctrl-Esc.
 // Note that this *doesn't* help with any missing Cmd inputs.
 // https://github.com/chromium/chromium/blob/a86a8a6bcfa438fa3ac2eba6f02b3ad1f8e0756f/ui/views/cocoa/bridged_content_view.mm#L816
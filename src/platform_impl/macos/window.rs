@@ -4,9 +4,9 @@
 
 use std::{
   collections::VecDeque,
-  convert::TryInto,
   f64,
   os::raw::c_void,
+  path::Path,
   sync::{
     atomic::{AtomicBool, Ordering},
     Arc, Mutex, Weak,
@@ -28,14 +28,14 @@ use crate::{
       monitor::{self, MonitorHandle, VideoMode},
       util::{self, IdRef},
       view::{self, new_view, CursorState},
-      window_delegate::new_delegate,
+      window_delegate::{new_delegate, TEXT_PBOARD_TYPES},
       OsError,
     },
     set_progress_indicator,
   },
   window::{
-    CursorIcon, Fullscreen, ProgressBarState, ResizeDirection, Theme, UserAttentionType,
-    WindowAttributes, WindowId as RootWindowId, WindowSizeConstraints,
+    clamp_frame_to_work_areas, CursorIcon, Fullscreen, ProgressBarState, ResizeDirection, Theme,
+    UserAttentionType, WindowAttributes, WindowId as RootWindowId, WindowSizeConstraints,
   },
 };
 use cocoa::{
@@ -47,8 +47,8 @@ use cocoa::{
   },
   base::{id, nil},
   foundation::{
-    NSArray, NSAutoreleasePool, NSDictionary, NSInteger, NSPoint, NSRect, NSSize, NSString,
-    NSTimeInterval, NSUInteger,
+    NSArray, NSAutoreleasePool, NSInteger, NSPoint, NSRect, NSSize, NSString, NSTimeInterval,
+    NSUInteger,
   },
 };
 use core_graphics::display::{CGDisplay, CGDisplayMode};
@@ -74,6 +74,38 @@ pub fn get_window_id(window_cocoa_id: id) -> Id {
   Id(window_cocoa_id as *const Object as _)
 }
 
+/// Returns the window number of the application's window with the given id, if it is still open.
+unsafe fn window_number(window_id: Id) -> Option<NSInteger> {
+  let windows: id = msg_send![NSApp(), windows];
+  (0..NSArray::count(windows))
+    .map(|i| NSArray::objectAtIndex(windows, i))
+    .find(|&ns_window| get_window_id(ns_window) == window_id)
+    .map(|ns_window| msg_send![ns_window, windowNumber])
+}
+
+/// Moves the window onto the visible frame of the nearest screen when its title bar isn't on any
+/// screen's visible frame.
+unsafe fn clamp_frame_to_visible_frames(ns_window: id) {
+  // in tao screen-coordinates, see `util::window_position`
+  let top_left_rect = |rect: NSRect| {
+    let top = util::bottom_left_to_top_left(rect);
+    (
+      rect.origin.x,
+      top,
+      rect.origin.x + rect.size.width,
+      top + rect.size.height,
+    )
+  };
+  let screens = NSScreen::screens(nil);
+  let visible_frames: Vec<_> = (0..NSArray::count(screens))
+    .map(|i| top_left_rect(NSScreen::visibleFrame(NSArray::objectAtIndex(screens, i))))
+    .collect();
+  let frame = top_left_rect(NSWindow::frame(ns_window));
+  if let Some((left, top)) = clamp_frame_to_work_areas(frame, &visible_frames, 1.0) {
+    ns_window.setFrameTopLeftPoint_(util::window_position(LogicalPosition::new(left, top)));
+  }
+}
+
 #[non_exhaustive]
 #[derive(Clone)]
 pub enum Parent {
@@ -96,6 +128,7 @@ pub struct PlatformSpecificWindowBuilderAttributes {
   pub traffic_light_inset: Option<Position>,
   pub automatic_tabbing: bool,
   pub tabbing_identifier: Option<String>,
+  pub active: Option<bool>,
 }
 
 impl Default for PlatformSpecificWindowBuilderAttributes {
@@ -115,10 +148,25 @@ impl Default for PlatformSpecificWindowBuilderAttributes {
       traffic_light_inset: None,
       automatic_tabbing: true,
       tabbing_identifier: None,
+      active: None,
     }
   }
 }
 
+/// Activates the application unless it hasn't finished launching yet, in which
+/// case `AppState::launched` takes care of it, or its activation policy is
+/// `Prohibited`.
+unsafe fn activate_app() {
+  use cocoa::appkit::NSApplicationActivationPolicy::NSApplicationActivationPolicyProhibited;
+
+  let ns_app = NSApp();
+  let is_running: BOOL = msg_send![ns_app, isRunning];
+  let policy: NSInteger = msg_send![ns_app, activationPolicy];
+  if is_running == YES && policy != NSApplicationActivationPolicyProhibited as NSInteger {
+    ns_app.activateIgnoringOtherApps_(YES);
+  }
+}
+
 unsafe fn create_view(
   ns_window: id,
   pl_attribs: &PlatformSpecificWindowBuilderAttributes,
@@ -165,7 +213,8 @@ fn create_window(
         Some(monitor_screen.unwrap_or_else(|| appkit::NSScreen::mainScreen(nil)))
       }
       Some(Fullscreen::Borderless(None)) => Some(appkit::NSScreen::mainScreen(nil)),
-      None => None,
+      // Sized normally so it can be restored, `set_fullscreen` covers the work area afterwards
+      Some(Fullscreen::BorderlessWorkArea(_)) | None => None,
     };
     let frame = match screen {
       Some(screen) => NSScreen::frame(screen),
@@ -291,6 +340,12 @@ fn create_window(
 
       if let Parent::ChildOf(parent) = pl_attrs.parent {
         let _: () = msg_send![parent as id, addChildWindow: *ns_window ordered: NSWindowOrderingMode::NSWindowAbove];
+
+        // Child windows inherit the content protection of their parent
+        let sharing_type: NSUInteger = msg_send![parent as id, sharingType];
+        if sharing_type == 0 {
+          let _: () = msg_send![*ns_window, setSharingType: 0];
+        }
       }
 
       if !pl_attrs.automatic_tabbing {
@@ -306,6 +361,8 @@ fn create_window(
       }
       if attrs.position.is_none() {
         ns_window.center();
+      } else if attrs.position_clamped {
+        clamp_frame_to_visible_frames(*ns_window);
       }
 
       ns_window
@@ -517,9 +574,13 @@ impl UnownedWindow {
       }
 
       // register for drag and drop operations.
+      let [url_type, text_type] = TEXT_PBOARD_TYPES.map(|ty| util::ns_string_id_ref(ty));
       let () = msg_send![
         *ns_window,
-        registerForDraggedTypes: NSArray::arrayWithObject(nil, appkit::NSFilenamesPboardType)
+        registerForDraggedTypes: NSArray::arrayWithObjects(
+          nil,
+          &[appkit::NSFilenamesPboardType, *url_type, *text_type]
+        )
       ];
     }
 
@@ -532,6 +593,8 @@ impl UnownedWindow {
     let maximized = win_attribs.maximized;
     let visible = win_attribs.visible;
     let focused = win_attribs.focused;
+    let below = win_attribs.below;
+    let active = pl_attribs.active;
     let decorations = win_attribs.decorations;
     let visible_on_all_workspaces = win_attribs.visible_on_all_workspaces;
     let inner_rect = win_attribs
@@ -572,12 +635,35 @@ impl UnownedWindow {
     // state, since otherwise we'll briefly see the window at normal size
     // before it transitions.
     if visible {
-      if focused {
-        // Tightly linked with `app_state::window_activation_hack`
-        unsafe { window.ns_window.makeKeyAndOrderFront_(nil) };
-      } else {
-        unsafe { window.ns_window.orderFront_(nil) };
+      match (focused, active) {
+        // Become key without activating the application, like a panel would.
+        (true, Some(false)) => unsafe {
+          window.ns_window.orderFront_(nil);
+          window.ns_window.makeKeyWindow();
+        },
+        (true, _) => {
+          // Tightly linked with `app_state::window_activation_hack`
+          unsafe { window.ns_window.makeKeyAndOrderFront_(nil) };
+          AppState::queue_activation(RootWindowId(window.id()));
+        }
+        (false, _) => unsafe {
+          match below.and_then(|id| window_number(id.0)) {
+            Some(number) => {
+              let _: () = msg_send![
+                *window.ns_window,
+                orderWindow: NSWindowOrderingMode::NSWindowBelow
+                relativeTo: number
+              ];
+            }
+            None => window.ns_window.orderFront_(nil),
+          }
+        },
+      }
+      if active == Some(true) {
+        unsafe { activate_app() };
       }
+      // Ensure the first frame is drawn without waiting for AppKit to dirty the view.
+      window.request_redraw();
     }
 
     if maximized {
@@ -610,13 +696,19 @@ impl UnownedWindow {
   pub fn title(&self) -> String {
     unsafe {
       let title = self.ns_window.title();
+      if title == nil {
+        return String::new();
+      }
       ns_string_to_rust(title)
     }
   }
 
   pub fn set_visible(&self, visible: bool) {
     match visible {
-      true => unsafe { util::make_key_and_order_front_sync(*self.ns_window) },
+      true => {
+        unsafe { util::make_key_and_order_front_sync(*self.ns_window) };
+        self.request_redraw();
+      }
       false => unsafe { util::order_out_sync(*self.ns_window) },
     }
   }
@@ -633,6 +725,12 @@ impl UnownedWindow {
     }
   }
 
+  #[inline]
+  pub fn activate(&self) {
+    unsafe { util::activate(*self.ns_window) };
+    self.request_redraw();
+  }
+
   #[inline]
   pub fn is_focused(&self) -> bool {
     unsafe {
@@ -641,6 +739,31 @@ impl UnownedWindow {
     }
   }
 
+  #[inline]
+  pub fn owner(&self) -> Option<Id> {
+    unsafe {
+      let parent: id = msg_send![*self.ns_window, parentWindow];
+      if parent == nil {
+        None
+      } else {
+        Some(get_window_id(parent))
+      }
+    }
+  }
+
+  #[inline]
+  pub fn owned_windows(&self) -> Vec<Id> {
+    unsafe {
+      let child_windows: id = msg_send![*self.ns_window, childWindows];
+      if child_windows == nil {
+        return Vec::new();
+      }
+      (0..child_windows.count())
+        .map(|i| get_window_id(child_windows.objectAtIndex(i)))
+        .collect()
+    }
+  }
+
   pub fn request_redraw(&self) {
     AppState::queue_redraw(RootWindowId(self.id()));
   }
@@ -848,6 +971,21 @@ impl UnownedWindow {
     Ok(())
   }
 
+  #[inline]
+  pub fn set_cursor_position_screen(&self, cursor_position: Position) -> Result<(), ExternalError> {
+    let logical_cursor_position = cursor_position.to_logical::<CGFloat>(self.scale_factor());
+    let point = appkit::CGPoint {
+      x: logical_cursor_position.x,
+      y: logical_cursor_position.y,
+    };
+    CGDisplay::warp_mouse_cursor_position(point)
+      .map_err(|e| ExternalError::Os(os_error!(OsError::CGError(e))))?;
+    CGDisplay::associate_mouse_and_mouse_cursor_position(true)
+      .map_err(|e| ExternalError::Os(os_error!(OsError::CGError(e))))?;
+
+    Ok(())
+  }
+
   #[inline]
   pub fn drag_window(&self) -> Result<(), ExternalError> {
     unsafe {
@@ -945,6 +1083,45 @@ impl UnownedWindow {
     self.set_maximized(maximized);
   }
 
+  /// Covers the visible frame of the screen, which excludes the menu bar and the dock, with an
+  /// untitled window. Unlike `Fullscreen::Borderless`, this doesn't use a separate space.
+  fn enter_work_area_fullscreen(&self, fullscreen: Option<Fullscreen>, screen: id) {
+    trace!("Locked shared state in `enter_work_area_fullscreen`");
+    let mut shared_state_lock = self.shared_state.lock().unwrap();
+    unsafe {
+      shared_state_lock.standard_frame = Some(NSWindow::frame(*self.ns_window));
+      shared_state_lock.saved_style = Some(self.ns_window.styleMask());
+    }
+    shared_state_lock.fullscreen = fullscreen;
+    drop(shared_state_lock);
+    trace!("Unlocked shared state in `enter_work_area_fullscreen`");
+
+    unsafe {
+      let mask = self.ns_window.styleMask()
+        & !(NSWindowStyleMask::NSTitledWindowMask | NSWindowStyleMask::NSResizableWindowMask);
+      self.set_style_mask_sync(mask);
+      NSWindow::setFrame_display_(*self.ns_window, NSScreen::visibleFrame(screen), YES);
+      NSWindow::setMovable_(*self.ns_window, NO);
+    }
+  }
+
+  /// Restores the window as it was before `enter_work_area_fullscreen`.
+  fn leave_work_area_fullscreen(&self) {
+    trace!("Locked shared state in `leave_work_area_fullscreen`");
+    let mut shared_state_lock = self.shared_state.lock().unwrap();
+    shared_state_lock.fullscreen = None;
+    let mask = self.saved_style(&mut shared_state_lock);
+    let frame = shared_state_lock.saved_standard_frame();
+    drop(shared_state_lock);
+    trace!("Unlocked shared state in `leave_work_area_fullscreen`");
+
+    self.set_style_mask_sync(mask);
+    unsafe {
+      NSWindow::setFrame_display_(*self.ns_window, frame, YES);
+      NSWindow::setMovable_(*self.ns_window, YES);
+    }
+  }
+
   #[inline]
   pub fn set_minimized(&self, minimized: bool) {
     let is_minimized: BOOL = unsafe { msg_send![*self.ns_window, isMiniaturized] };
@@ -954,6 +1131,21 @@ impl UnownedWindow {
     }
 
     if minimized {
+      // A window in its own fullscreen space can't be miniaturized, trying to leaves an empty
+      // space behind instead.
+      let in_fullscreen_space =
+        unsafe { self.ns_window.styleMask() }.contains(NSWindowStyleMask::NSFullScreenWindowMask);
+      let in_fullscreen_transition = {
+        trace!("Locked shared state in `set_minimized`");
+        let in_fullscreen_transition = self.shared_state.lock().unwrap().in_fullscreen_transition;
+        trace!("Unlocked shared state in `set_minimized`");
+        in_fullscreen_transition
+      };
+      if in_fullscreen_space || in_fullscreen_transition {
+        warn!("`Window::set_minimized` is ignored while the window is in native fullscreen");
+        return;
+      }
+
       unsafe {
         NSWindow::miniaturize_(*self.ns_window, *self.ns_window);
       }
@@ -1058,7 +1250,7 @@ impl UnownedWindow {
       trace!("Unlocked shared state in `set_fullscreen`");
       return;
     }
-    let old_fullscreen = shared_state_lock.fullscreen.clone();
+    let mut old_fullscreen = shared_state_lock.fullscreen.clone();
     if fullscreen == old_fullscreen {
       trace!("Unlocked shared state in `set_fullscreen`");
       return;
@@ -1066,12 +1258,41 @@ impl UnownedWindow {
     trace!("Unlocked shared state in `set_fullscreen`");
     drop(shared_state_lock);
 
+    // The work area is covered by resizing the window rather than through native fullscreen,
+    // so leave it first and carry on as if the window wasn't fullscreen.
+    if let Some(Fullscreen::BorderlessWorkArea(_)) = old_fullscreen {
+      self.leave_work_area_fullscreen();
+      if fullscreen.is_none() {
+        return;
+      }
+      old_fullscreen = None;
+    }
+
+    if let Some(Fullscreen::BorderlessWorkArea(ref monitor)) = fullscreen {
+      if old_fullscreen.is_some() {
+        // Leaving native fullscreen is animated, come back once it's done
+        trace!("Locked shared state in `set_fullscreen`");
+        self.shared_state.lock().unwrap().target_fullscreen = Some(fullscreen.clone());
+        trace!("Unlocked shared state in `set_fullscreen`");
+        self.set_fullscreen(None);
+        return;
+      }
+      let RootMonitorHandle { inner: monitor } = monitor
+        .clone()
+        .unwrap_or_else(|| self.current_monitor_inner());
+      match monitor.ns_screen() {
+        Some(screen) => self.enter_work_area_fullscreen(fullscreen, screen),
+        None => warn!("Can't find the screen to cover the work area of"),
+      }
+      return;
+    }
+
     // If the fullscreen is on a different monitor, we must move the window
     // to that monitor before we toggle fullscreen (as `toggleFullScreen`
     // does not take a screen parameter, but uses the current screen)
     if let Some(ref fullscreen) = fullscreen {
       let new_screen = match fullscreen {
-        Fullscreen::Borderless(borderless) => {
+        Fullscreen::Borderless(borderless) | Fullscreen::BorderlessWorkArea(borderless) => {
           let RootMonitorHandle { inner: monitor } = borderless
             .clone()
             .unwrap_or_else(|| self.current_monitor_inner());
@@ -1111,6 +1332,17 @@ impl UnownedWindow {
 
       let display_id = video_mode.monitor().inner.native_identifier();
 
+      // Release the display we're leaving before changing the video mode of the new one, as
+      // restoring it restores the configuration of every display
+      if let Some(Fullscreen::Exclusive(RootVideoMode {
+        video_mode: ref old_mode,
+      })) = old_fullscreen
+      {
+        if old_mode.monitor() != video_mode.monitor() {
+          unsafe { util::restore_display_mode_sync(old_mode.monitor().inner.native_identifier()) };
+        }
+      }
+
       let mut fade_token = ffi::kCGDisplayFadeReservationInvalidToken;
 
       if matches!(old_fullscreen, Some(Fullscreen::Borderless(_))) {
@@ -1311,12 +1543,28 @@ impl UnownedWindow {
     // `setRepresentedFilename`, but that's semantically distinct and should
     // only be used when the window is in some way representing a specific
     // file/directory. For instance, Terminal.app uses this for the CWD.
-    // Anyway, that should eventually be implemented as
-    // `WindowBuilderExt::with_represented_file` or something, and doesn't
-    // have anything to do with `set_window_icon`.
+    // Anyway, that's implemented as `WindowExtMacOS::set_represented_filename`
+    // and doesn't have anything to do with `set_window_icon`.
     // https://developer.apple.com/library/content/documentation/Cocoa/Conceptual/WinPanel/Tasks/SettingWindowTitle.html
   }
 
+  #[inline]
+  pub fn window_icon(&self) -> Option<Icon> {
+    None
+  }
+
+  #[inline]
+  pub fn set_drag_region(&self, region: Option<(Position, Size)>) {
+    let scale_factor = self.scale_factor();
+    let region = region.map(|(position, size)| {
+      (
+        position.to_logical(scale_factor),
+        size.to_logical(scale_factor),
+      )
+    });
+    unsafe { view::set_drag_region(*self.ns_view, region) };
+  }
+
   #[inline]
   pub fn set_ime_position(&self, spot: Position) {
     let scale_factor = self.scale_factor();
@@ -1331,6 +1579,9 @@ impl UnownedWindow {
     }
   }
 
+  #[inline]
+  pub fn set_ime_allowed(&self, _allowed: bool) {}
+
   #[inline]
   pub fn request_user_attention(&self, request_type: Option<UserAttentionType>) {
     let ns_request_type = request_type.map(|ty| match ty {
@@ -1349,12 +1600,8 @@ impl UnownedWindow {
   pub(crate) fn current_monitor_inner(&self) -> RootMonitorHandle {
     unsafe {
       let screen: id = msg_send![*self.ns_window, screen];
-      let desc = NSScreen::deviceDescription(screen);
-      let key = util::ns_string_id_ref("NSScreenNumber");
-      let value = NSDictionary::valueForKey_(desc, *key);
-      let display_id: NSUInteger = msg_send![value, unsignedIntegerValue];
       RootMonitorHandle {
-        inner: MonitorHandle::new(display_id.try_into().unwrap()),
+        inner: monitor::from_ns_screen(screen),
       }
     }
   }
@@ -1428,9 +1675,9 @@ impl UnownedWindow {
   }
 
   pub fn set_theme(&self, theme: Option<Theme>) {
+    // `current_theme` is updated by the appearance observer right before `ThemeChanged` is
+    // emitted, so the getter always matches the last event.
     set_ns_theme(theme);
-    let mut state = self.shared_state.lock().unwrap();
-    state.current_theme = theme.unwrap_or_else(get_ns_theme);
   }
 
   pub fn set_content_protection(&self, enabled: bool) {
@@ -1439,6 +1686,25 @@ impl UnownedWindow {
     }
   }
 
+  pub fn is_content_protected(&self) -> bool {
+    // `NSWindowSharingNone`
+    let sharing_type: NSUInteger = unsafe { msg_send![*self.ns_window, sharingType] };
+    sharing_type == 0
+  }
+
+  pub fn set_content_protection_with_owned_windows(&self, enabled: bool) {
+    unsafe {
+      let mut windows = vec![*self.ns_window];
+      while let Some(window) = windows.pop() {
+        let _: () = msg_send![window, setSharingType: !enabled as i32];
+        let child_windows: id = msg_send![window, childWindows];
+        if child_windows != nil {
+          windows.extend((0..child_windows.count()).map(|i| child_windows.objectAtIndex(i)));
+        }
+      }
+    }
+  }
+
   pub fn set_visible_on_all_workspaces(&self, visible: bool) {
     unsafe {
       let mut collection_behavior = self.ns_window.collectionBehavior();
@@ -1599,6 +1865,15 @@ impl WindowExtMacOS for UnownedWindow {
     }
   }
 
+  #[inline]
+  fn set_represented_filename<P: AsRef<Path>>(&self, path: P) {
+    unsafe {
+      let filename = NSString::alloc(nil).init_str(&path.as_ref().to_string_lossy());
+      let _: () = msg_send![*self.ns_window, setRepresentedFilename: filename];
+      let _: () = msg_send![filename, release];
+    }
+  }
+
   #[inline]
   fn set_allows_automatic_window_tabbing(&self, enabled: bool) {
     unsafe {
@@ -1649,11 +1924,74 @@ impl WindowExtMacOS for UnownedWindow {
         .setTitlebarAppearsTransparent_(transparent as BOOL);
     }
   }
+
+  #[inline]
+  fn set_subtitle(&self, subtitle: &str) {
+    unsafe {
+      let has_subtitle: BOOL = msg_send![*self.ns_window, respondsToSelector: sel!(setSubtitle:)];
+      if has_subtitle == NO {
+        return;
+      }
+      let subtitle = NSString::alloc(nil).init_str(subtitle);
+      let _: () = msg_send![*self.ns_window, setSubtitle: subtitle];
+      let _: () = msg_send![subtitle, release];
+    }
+  }
+
+  #[inline]
+  fn set_window_buttons_visible(&self, visible: bool) {
+    unsafe {
+      for button in [
+        NSWindowButton::NSWindowCloseButton,
+        NSWindowButton::NSWindowMiniaturizeButton,
+        NSWindowButton::NSWindowZoomButton,
+      ] {
+        let button = self.ns_window.standardWindowButton_(button);
+        let _: () = msg_send![button, setHidden: !visible as BOOL];
+      }
+    }
+  }
+
+  #[inline]
+  fn window_buttons_visible(&self) -> bool {
+    unsafe {
+      let close = self
+        .ns_window
+        .standardWindowButton_(NSWindowButton::NSWindowCloseButton);
+      if close == nil {
+        return false;
+      }
+      let hidden: BOOL = msg_send![close, isHidden];
+      hidden == NO
+    }
+  }
 }
 
 impl Drop for UnownedWindow {
   fn drop(&mut self) {
     trace!("Dropping `UnownedWindow` ({:?})", self as *mut _);
+    // Don't leave the display captured with our video mode behind
+    let exclusive = {
+      trace!("Locked shared state in `drop`");
+      let mut shared_state_lock = self.shared_state.lock().unwrap();
+      let exclusive = match shared_state_lock.fullscreen {
+        Some(Fullscreen::Exclusive(RootVideoMode { ref video_mode })) => Some((
+          video_mode.monitor().inner.native_identifier(),
+          shared_state_lock.save_presentation_opts.take(),
+        )),
+        _ => None,
+      };
+      trace!("Unlocked shared state in `drop`");
+      exclusive
+    };
+    if let Some((display_id, presentation_opts)) = exclusive {
+      unsafe {
+        if let Some(presentation_opts) = presentation_opts {
+          NSApp().setPresentationOptions_(presentation_opts);
+        }
+        util::restore_display_mode_async(display_id);
+      }
+    }
     // Close the window if it has not yet been closed.
     if *self.ns_window != nil {
       unsafe { util::close_async(self.ns_window.clone()) };
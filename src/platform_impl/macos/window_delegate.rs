@@ -9,7 +9,7 @@ use std::{
 };
 
 use cocoa::{
-  appkit::{self, NSApplicationPresentationOptions, NSView, NSWindow},
+  appkit::{self, NSApplicationPresentationOptions, NSView, NSWindow, NSWindowStyleMask},
   base::{id, nil},
   foundation::{NSAutoreleasePool, NSString, NSUInteger},
 };
@@ -29,7 +29,7 @@ use crate::{
     view::ViewState,
     window::{get_ns_theme, get_window_id, UnownedWindow},
   },
-  window::{Fullscreen, WindowId},
+  window::{Fullscreen, WindowId, WindowMode},
 };
 
 pub struct WindowDelegateState {
@@ -56,6 +56,9 @@ pub struct WindowDelegateState {
   // Used to prevent resized events from being fired
   // when we are using our workaround in the `is_zoomed` function.
   is_checking_zoomed_in: bool,
+
+  // The mode last reported with `WindowModeChanged`.
+  window_mode: WindowMode,
 }
 
 impl WindowDelegateState {
@@ -69,7 +72,9 @@ impl WindowDelegateState {
       previous_position: None,
       previous_scale_factor: scale_factor,
       is_checking_zoomed_in: false,
+      window_mode: WindowMode::Normal,
     };
+    delegate_state.window_mode = delegate_state.current_window_mode();
     if (scale_factor - 1.0).abs() > f64::EPSILON {
       delegate_state.emit_static_scale_factor_changed_event();
     }
@@ -119,6 +124,36 @@ impl WindowDelegateState {
     self.emit_event(WindowEvent::Resized(size));
   }
 
+  fn current_window_mode(&self) -> WindowMode {
+    unsafe {
+      let ns_window = *self.ns_window;
+      let style_mask = ns_window.styleMask();
+      let is_miniaturized: BOOL = msg_send![ns_window, isMiniaturized];
+      let is_zoomed: BOOL = msg_send![ns_window, isZoomed];
+      // `isZoomed` can't be trusted without these, and `UnownedWindow::is_zoomed`'s workaround
+      // would resize the window from within the delegate.
+      let zoomable = style_mask
+        .contains(NSWindowStyleMask::NSTitledWindowMask | NSWindowStyleMask::NSResizableWindowMask);
+      if is_miniaturized == YES {
+        WindowMode::Minimized
+      } else if style_mask.contains(NSWindowStyleMask::NSFullScreenWindowMask) {
+        WindowMode::Fullscreen
+      } else if zoomable && is_zoomed == YES {
+        WindowMode::Maximized
+      } else {
+        WindowMode::Normal
+      }
+    }
+  }
+
+  fn emit_window_mode_event(&mut self) {
+    let window_mode = self.current_window_mode();
+    if window_mode != self.window_mode {
+      self.window_mode = window_mode;
+      self.emit_event(WindowEvent::WindowModeChanged(window_mode));
+    }
+  }
+
   fn emit_move_event(&mut self) {
     let rect = unsafe { NSWindow::frame(*self.ns_window) };
     let x = rect.origin.x as f64;
@@ -203,6 +238,18 @@ lazy_static! {
       sel!(windowDidResignKey:),
       window_did_resign_key as extern "C" fn(&Object, Sel, id),
     );
+    decl.add_method(
+      sel!(windowDidMiniaturize:),
+      window_did_miniaturize as extern "C" fn(&Object, Sel, id),
+    );
+    decl.add_method(
+      sel!(windowDidDeminiaturize:),
+      window_did_deminiaturize as extern "C" fn(&Object, Sel, id),
+    );
+    decl.add_method(
+      sel!(windowDidChangeOcclusionState:),
+      window_did_change_occlusion_state as extern "C" fn(&Object, Sel, id),
+    );
 
     decl.add_method(
       sel!(draggingEntered:),
@@ -344,6 +391,7 @@ extern "C" fn window_did_resize(this: &Object, _: Sel, _: id) {
     if !state.is_checking_zoomed_in {
       state.emit_resize_event();
       state.emit_move_event();
+      state.emit_window_mode_event();
     }
   });
   trace!("Completed `windowDidResize:`");
@@ -376,6 +424,34 @@ extern "C" fn window_did_become_key(this: &Object, _: Sel, _: id) {
   trace!("Completed `windowDidBecomeKey:`");
 }
 
+extern "C" fn window_did_miniaturize(this: &Object, _: Sel, _: id) {
+  trace!("Triggered `windowDidMiniaturize:`");
+  with_state(this, |state| {
+    state.emit_event(WindowEvent::Minimized(true));
+    state.emit_window_mode_event();
+  });
+  trace!("Completed `windowDidMiniaturize:`");
+}
+
+extern "C" fn window_did_deminiaturize(this: &Object, _: Sel, _: id) {
+  trace!("Triggered `windowDidDeminiaturize:`");
+  with_state(this, |state| {
+    state.emit_event(WindowEvent::Minimized(false));
+    state.emit_window_mode_event();
+  });
+  trace!("Completed `windowDidDeminiaturize:`");
+}
+
+extern "C" fn window_did_change_occlusion_state(this: &Object, _: Sel, _: id) {
+  trace!("Triggered `windowDidChangeOcclusionState:`");
+  with_state(this, |state| {
+    // `NSWindowOcclusionStateVisible`
+    let occlusion_state: NSUInteger = unsafe { msg_send![*state.ns_window, occlusionState] };
+    state.emit_event(WindowEvent::Occluded(occlusion_state & (1 << 1) == 0));
+  });
+  trace!("Completed `windowDidChangeOcclusionState:`");
+}
+
 extern "C" fn window_did_resign_key(this: &Object, _: Sel, _: id) {
   trace!("Triggered `windowDidResignKey:`");
   with_state(this, |state| {
@@ -445,7 +521,10 @@ extern "C" fn prepare_for_drag_operation(_: &Object, _: Sel, _: id) -> BOOL {
 extern "C" fn perform_drag_operation(this: &Object, _: Sel, sender: id) -> BOOL {
   trace!("Triggered `performDragOperation:`");
 
-  use cocoa::{appkit::NSPasteboard, foundation::NSFastEnumeration};
+  use cocoa::{
+    appkit::NSPasteboard,
+    foundation::{NSArray, NSFastEnumeration},
+  };
   use std::path::PathBuf;
 
   let pb: id = unsafe { msg_send![sender, draggingPasteboard] };
@@ -464,10 +543,38 @@ extern "C" fn perform_drag_operation(this: &Object, _: Sel, sender: id) -> BOOL
     }
   }
 
+  if filenames == nil || unsafe { filenames.count() } == 0 {
+    if let Some(event) = unsafe { dropped_text_event(pb) } {
+      with_state(this, |state| state.emit_event(event));
+    }
+  }
+
   trace!("Completed `performDragOperation:`");
   YES
 }
 
+/// The pasteboard types, besides filenames, the window accepts drops of.
+pub(crate) const TEXT_PBOARD_TYPES: [&str; 2] = ["public.url", "public.utf8-plain-text"];
+
+/// Reads a dropped URL, or failing that plain text, from the dragging pasteboard.
+unsafe fn dropped_text_event(pb: id) -> Option<WindowEvent<'static>> {
+  let [url_type, text_type] = TEXT_PBOARD_TYPES.map(|ty| util::ns_string_id_ref(ty));
+
+  let url: id = msg_send![pb, stringForType: *url_type];
+  if url != nil {
+    if let Ok(url) = url::Url::parse(&util::ns_string_to_rust(url)) {
+      return Some(WindowEvent::DroppedUrl(url));
+    }
+  }
+
+  let text: id = msg_send![pb, stringForType: *text_type];
+  if text != nil {
+    return Some(WindowEvent::DroppedText(util::ns_string_to_rust(text)));
+  }
+
+  None
+}
+
 /// Invoked when the dragging operation is complete
 extern "C" fn conclude_drag_operation(_: &Object, _: Sel, _: id) {
   trace!("Triggered `concludeDragOperation:`");
@@ -503,7 +610,7 @@ extern "C" fn window_will_enter_fullscreen(this: &Object, _: Sel, _: id) {
         Some(Fullscreen::Borderless(_)) => (),
         // Otherwise, we must've reached fullscreen by the user clicking
         // on the green fullscreen button. Update state!
-        None => {
+        Some(Fullscreen::BorderlessWorkArea(_)) | None => {
           let current_monitor = Some(window.current_monitor_inner());
           shared_state.fullscreen = Some(Fullscreen::Borderless(current_monitor))
         }
@@ -580,6 +687,7 @@ extern "C" fn window_did_enter_fullscreen(this: &Object, _: Sel, _: id) {
     });
     state.emit_resize_event();
     state.emit_move_event();
+    state.emit_window_mode_event();
   });
   trace!("Completed `windowDidEnterFullscreen:`");
 }
@@ -602,6 +710,7 @@ extern "C" fn window_did_exit_fullscreen(this: &Object, _: Sel, _: id) {
     });
     state.emit_resize_event();
     state.emit_move_event();
+    state.emit_window_mode_event();
   });
   trace!("Completed `windowDidExitFullscreen:`");
 }
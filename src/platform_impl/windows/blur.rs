@@ -0,0 +1,77 @@
+// Copyright 2014-2021 The winit contributors
+// Copyright 2021-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+
+//! Blur behind windows on Windows 10, through the undocumented `SetWindowCompositionAttribute`
+//! which is also what the system uses for its own blurred surfaces.
+
+use once_cell::sync::Lazy;
+use windows::{
+  core::s,
+  Win32::{
+    Foundation::{BOOL, HWND},
+    System::LibraryLoader::{GetModuleHandleA, GetProcAddress},
+  },
+};
+
+use std::ffi::c_void;
+
+const WCA_ACCENT_POLICY: u32 = 19;
+
+const ACCENT_DISABLED: u32 = 0;
+const ACCENT_ENABLE_BLURBEHIND: u32 = 3;
+
+#[repr(C)]
+struct AccentPolicy {
+  accent_state: u32,
+  accent_flags: u32,
+  gradient_color: u32,
+  animation_id: u32,
+}
+
+#[repr(C)]
+struct WindowCompositionAttribData {
+  attrib: u32,
+  data: *mut c_void,
+  size: usize,
+}
+
+type SetWindowCompositionAttribute =
+  unsafe extern "system" fn(HWND, *mut WindowCompositionAttribData) -> BOOL;
+
+static SET_WINDOW_COMPOSITION_ATTRIBUTE: Lazy<Option<SetWindowCompositionAttribute>> =
+  Lazy::new(|| unsafe {
+    if windows_version::OsVersion::current().major < 10 {
+      return None;
+    }
+
+    let user32 = GetModuleHandleA(s!("user32.dll")).ok()?;
+    GetProcAddress(user32, s!("SetWindowCompositionAttribute"))
+      .map(|handle| std::mem::transmute(handle))
+  });
+
+/// Enables or disables the blur behind `hwnd`, returning whether it could be applied.
+pub fn set_blur(hwnd: HWND, blur: bool) -> bool {
+  let set_window_composition_attribute = match *SET_WINDOW_COMPOSITION_ATTRIBUTE {
+    Some(f) => f,
+    None => return false,
+  };
+
+  let mut policy = AccentPolicy {
+    accent_state: if blur {
+      ACCENT_ENABLE_BLURBEHIND
+    } else {
+      ACCENT_DISABLED
+    },
+    accent_flags: 0,
+    gradient_color: 0,
+    animation_id: 0,
+  };
+  let mut data = WindowCompositionAttribData {
+    attrib: WCA_ACCENT_POLICY,
+    data: &mut policy as *mut _ as _,
+    size: std::mem::size_of::<AccentPolicy>(),
+  };
+
+  unsafe { set_window_composition_attribute(hwnd, &mut data) }.as_bool()
+}
@@ -0,0 +1,302 @@
+// Copyright 2014-2021 The winit contributors
+// Copyright 2021-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+
+//! Cursors that have no system equivalent, synthesized from monochrome bitmaps.
+//!
+//! In the bitmaps below, `#` is black, `.` is white and anything else is transparent.
+
+use std::{
+  cell::{Cell, RefCell},
+  collections::HashMap,
+};
+
+use windows::Win32::{
+  Foundation::{HINSTANCE, HMODULE, POINT, WPARAM},
+  System::Threading::GetCurrentThreadId,
+  UI::WindowsAndMessaging::{
+    CreateCursor, GetCursorPos, GetWindowThreadProcessId, LoadCursorW, SendMessageW,
+    WindowFromPoint, HCURSOR, WM_MOUSEMOVE, WM_NCHITTEST, WM_SETCURSOR,
+  },
+};
+
+use super::util;
+use crate::window::CursorIcon;
+
+const SIZE: usize = 32;
+
+const ARROW: &[&str] = &[
+  "#",
+  "##",
+  "#.#",
+  "#..#",
+  "#...#",
+  "#....#",
+  "#.....#",
+  "#......#",
+  "#.......#",
+  "#........#",
+  "#.....#####",
+  "#..#..#",
+  "#.# #..#",
+  "##  #..#",
+  "#    #..#",
+  "     #..#",
+  "      ##",
+];
+
+/// Row and column the badges of `Copy`, `Alias` and `ContextMenu` are drawn at, next to `ARROW`.
+const BADGE_OFFSET: (usize, usize) = (12, 10);
+
+const COPY_BADGE: &[&str] = &[
+  "#########",
+  "#.......#",
+  "#...#...#",
+  "#...#...#",
+  "#.#####.#",
+  "#...#...#",
+  "#...#...#",
+  "#.......#",
+  "#########",
+];
+
+const ALIAS_BADGE: &[&str] = &[
+  "#########",
+  "#.......#",
+  "#..####.#",
+  "#....##.#",
+  "#...#.#.#",
+  "#..#..#.#",
+  "#.#.....#",
+  "#.......#",
+  "#########",
+];
+
+const CONTEXT_MENU_BADGE: &[&str] = &[
+  "#########",
+  "#.......#",
+  "#.#####.#",
+  "#.......#",
+  "#.#####.#",
+  "#.......#",
+  "#.#####.#",
+  "#.......#",
+  "#########",
+];
+
+const CELL: &[&str] = &[
+  "    ####",
+  "    #..#",
+  "    #..#",
+  "    #..#",
+  "#####..#####",
+  "#..........#",
+  "#..........#",
+  "#####..#####",
+  "    #..#",
+  "    #..#",
+  "    #..#",
+  "    ####",
+];
+
+const ZOOM_IN: &[&str] = &[
+  "   #####",
+  "  #.....#",
+  " #.......#",
+  "#....#....#",
+  "#....#....#",
+  "#..#####..#",
+  "#....#....#",
+  "#....#....#",
+  " #.......#",
+  "  #.....##",
+  "   ######.#",
+  "        #..#",
+  "         #..#",
+  "          #..#",
+  "           #..#",
+  "            ##",
+];
+
+const ZOOM_OUT: &[&str] = &[
+  "   #####",
+  "  #.....#",
+  " #.......#",
+  "#.........#",
+  "#.........#",
+  "#..#####..#",
+  "#.........#",
+  "#.........#",
+  " #.......#",
+  "  #.....##",
+  "   ######.#",
+  "        #..#",
+  "         #..#",
+  "          #..#",
+  "           #..#",
+  "            ##",
+];
+
+const GRAB: &[&str] = &[
+  "       ##",
+  "   ## #..#",
+  "  #..##..# ##",
+  "  #..##..##..#",
+  "   #..#..#...#",
+  "   #..#..#..#.##",
+  " ## #.......#..#",
+  "#..##..........#",
+  "#...#.........#",
+  " #............#",
+  "  #...........#",
+  "  #..........#",
+  "   #.........#",
+  "    #.......#",
+  "     #......#",
+  "     #......#",
+  "     ########",
+];
+
+const GRABBING: &[&str] = &[
+  "   ## ## ##",
+  "  #..#..#..##",
+  "  #........#.#",
+  " ##..........#",
+  "#............#",
+  "#...........#",
+  " #..........#",
+  "  #.........#",
+  "   #.......#",
+  "    #......#",
+  "    #......#",
+  "    ########",
+];
+
+/// Rows of pixels drawn at a row and column offset.
+type Layer = (&'static [&'static str], (usize, usize));
+/// Layers drawn on top of each other, plus the hotspot.
+type Bitmap = (&'static [Layer], (i32, i32));
+
+fn bitmap(cursor: CursorIcon) -> Option<Bitmap> {
+  Some(match cursor {
+    CursorIcon::Copy => (&[(ARROW, (0, 0)), (COPY_BADGE, BADGE_OFFSET)], (0, 0)),
+    CursorIcon::Alias => (&[(ARROW, (0, 0)), (ALIAS_BADGE, BADGE_OFFSET)], (0, 0)),
+    CursorIcon::ContextMenu => (
+      &[(ARROW, (0, 0)), (CONTEXT_MENU_BADGE, BADGE_OFFSET)],
+      (0, 0),
+    ),
+    CursorIcon::Cell => (&[(CELL, (0, 0))], (5, 5)),
+    CursorIcon::ZoomIn => (&[(ZOOM_IN, (0, 0))], (5, 5)),
+    CursorIcon::ZoomOut => (&[(ZOOM_OUT, (0, 0))], (5, 5)),
+    CursorIcon::Grab => (&[(GRAB, (0, 0))], (8, 8)),
+    CursorIcon::Grabbing => (&[(GRABBING, (0, 0))], (7, 5)),
+    _ => return None,
+  })
+}
+
+/// Builds the AND and XOR planes of a monochrome cursor from `layers`.
+fn planes(layers: &[Layer]) -> ([u8; SIZE * SIZE / 8], [u8; SIZE * SIZE / 8]) {
+  // Transparent: AND bit set, XOR bit clear.
+  let mut and = [0xff; SIZE * SIZE / 8];
+  let mut xor = [0; SIZE * SIZE / 8];
+  for (rows, (top, left)) in layers {
+    for (y, row) in rows.iter().enumerate() {
+      for (x, pixel) in row.chars().enumerate() {
+        let (y, x) = (top + y, left + x);
+        if y >= SIZE || x >= SIZE {
+          continue;
+        }
+        let (byte, bit) = ((y * SIZE + x) / 8, 0x80 >> (x % 8));
+        match pixel {
+          '#' => {
+            and[byte] &= !bit;
+            xor[byte] &= !bit;
+          }
+          '.' => {
+            and[byte] &= !bit;
+            xor[byte] |= bit;
+          }
+          _ => (),
+        }
+      }
+    }
+  }
+  (and, xor)
+}
+
+thread_local! {
+  static SYNTHESIZED_CURSORS: RefCell<HashMap<CursorIcon, HCURSOR>> = RefCell::new(HashMap::new());
+  static BUSY: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Whether the busy cursor is shown over the windows of the current thread, in place of
+/// their own cursor.
+pub fn is_busy() -> bool {
+  BUSY.with(Cell::get)
+}
+
+/// Shows or hides the busy cursor over the windows of the current thread.
+pub fn set_busy(busy: bool) {
+  BUSY.with(|b| b.set(busy));
+  refresh();
+}
+
+/// Replays `WM_SETCURSOR` for the window of the current thread under the cursor, so it picks up
+/// a cursor change right away instead of on the next mouse move. Each window then shows its own
+/// cursor, which a plain `SetCursor` would override for whichever window is under the mouse.
+pub fn refresh() {
+  unsafe {
+    let mut pos = POINT::default();
+    if GetCursorPos(&mut pos).is_err() {
+      return;
+    }
+    let hwnd = WindowFromPoint(pos);
+    if hwnd.is_invalid() || GetWindowThreadProcessId(hwnd, None) != GetCurrentThreadId() {
+      return;
+    }
+    let hit_test = SendMessageW(
+      hwnd,
+      WM_NCHITTEST,
+      WPARAM(0),
+      util::MAKELPARAM(pos.x as i16, pos.y as i16),
+    );
+    SendMessageW(
+      hwnd,
+      WM_SETCURSOR,
+      WPARAM(hwnd.0 as _),
+      util::MAKELPARAM(hit_test.0 as i16, WM_MOUSEMOVE as i16),
+    );
+  }
+}
+
+/// Returns the cursor to show for `cursor`, falling back to a synthesized one for the icons
+/// Windows has no system cursor for.
+pub fn load_cursor(cursor: CursorIcon) -> Option<HCURSOR> {
+  let (layers, (x, y)) = match bitmap(cursor) {
+    Some(bitmap) => bitmap,
+    None => return unsafe { LoadCursorW(HMODULE::default(), cursor.to_windows_cursor()) }.ok(),
+  };
+
+  SYNTHESIZED_CURSORS.with(|cursors| {
+    let mut cursors = cursors.borrow_mut();
+    if let Some(hcursor) = cursors.get(&cursor) {
+      return Some(*hcursor);
+    }
+
+    let (and, xor) = planes(layers);
+    let hcursor = unsafe {
+      CreateCursor(
+        HINSTANCE(util::get_instance_handle().0),
+        x,
+        y,
+        SIZE as i32,
+        SIZE as i32,
+        and.as_ptr() as _,
+        xor.as_ptr() as _,
+      )
+    }
+    .ok()?;
+    cursors.insert(cursor, hcursor);
+    Some(hcursor)
+  })
+}
@@ -143,6 +143,19 @@ pub fn try_window_theme(
   }
 }
 
+/// Returns the theme `try_window_theme` would pick, without applying it to a window.
+pub fn resolve_theme(preferred_theme: Option<Theme>) -> Theme {
+  if !*DARK_MODE_SUPPORTED {
+    return Theme::Light;
+  }
+
+  match preferred_theme {
+    Some(theme) => theme,
+    None if should_use_dark_mode() => Theme::Dark,
+    None => Theme::Light,
+  }
+}
+
 pub fn allow_dark_mode_for_window(hwnd: HWND, is_dark_mode: bool) {
   const UXTHEME_ALLOWDARKMODEFORWINDOW_ORDINAL: u16 = 133;
   type AllowDarkModeForWindow = unsafe extern "system" fn(HWND, bool) -> bool;
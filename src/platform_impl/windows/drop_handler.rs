@@ -5,13 +5,16 @@
 use std::{cell::UnsafeCell, ffi::OsString, os::windows::ffi::OsStringExt, path::PathBuf, ptr};
 
 use windows::{
-  core::implement,
+  core::{implement, w},
   Win32::{
     Foundation::{self as win32f, HWND, POINTL},
     System::{
       Com::{IDataObject, DVASPECT_CONTENT, FORMATETC, TYMED_HGLOBAL},
+      DataExchange::RegisterClipboardFormatW,
+      Memory::{GlobalLock, GlobalSize, GlobalUnlock},
       Ole::{
-        IDropTarget, IDropTarget_Impl, CF_HDROP, DROPEFFECT, DROPEFFECT_COPY, DROPEFFECT_NONE,
+        IDropTarget, IDropTarget_Impl, ReleaseStgMedium, CF_HDROP, CF_UNICODETEXT, DROPEFFECT,
+        DROPEFFECT_COPY, DROPEFFECT_NONE,
       },
       SystemServices::MODIFIERKEYS_FLAGS,
     },
@@ -41,17 +44,57 @@ impl FileDropHandler {
     }
   }
 
-  unsafe fn iterate_filenames<F>(data_obj: Option<&IDataObject>, callback: F) -> Option<HDROP>
-  where
-    F: Fn(PathBuf),
-  {
-    let drop_format = FORMATETC {
-      cfFormat: CF_HDROP.0 as u16,
+  fn format(cf_format: u16) -> FORMATETC {
+    FORMATETC {
+      cfFormat: cf_format,
       ptd: ptr::null_mut(),
       dwAspect: DVASPECT_CONTENT.0 as u32,
       lindex: -1,
       tymed: TYMED_HGLOBAL.0 as u32,
+    }
+  }
+
+  /// The clipboard format browsers use for dragged links.
+  unsafe fn url_format() -> u16 {
+    RegisterClipboardFormatW(w!("UniformResourceLocatorW")) as u16
+  }
+
+  /// Whether the dragged item carries text or a URL, as opposed to files.
+  unsafe fn has_text(data_obj: Option<&IDataObject>) -> bool {
+    match data_obj {
+      Some(data_obj) => [Self::url_format(), CF_UNICODETEXT.0]
+        .iter()
+        .any(|&cf_format| data_obj.QueryGetData(&Self::format(cf_format)).is_ok()),
+      None => false,
+    }
+  }
+
+  /// Reads a UTF-16 string stored in the given format, up to its null terminator.
+  unsafe fn get_text(data_obj: Option<&IDataObject>, cf_format: u16) -> Option<String> {
+    let mut medium = data_obj?.GetData(&Self::format(cf_format)).ok()?;
+    let hglobal = medium.u.hGlobal;
+    let data = GlobalLock(hglobal) as *const u16;
+    let text = if data.is_null() {
+      None
+    } else {
+      // The drop source isn't required to null-terminate the text, so never
+      // scan past the end of the allocation.
+      let capacity = GlobalSize(hglobal) / std::mem::size_of::<u16>();
+      let units = std::slice::from_raw_parts(data, capacity);
+      let len = units.iter().position(|&c| c == 0).unwrap_or(capacity);
+      let text = String::from_utf16_lossy(&units[..len]);
+      let _ = GlobalUnlock(hglobal);
+      Some(text)
     };
+    ReleaseStgMedium(&mut medium);
+    text
+  }
+
+  unsafe fn iterate_filenames<F>(data_obj: Option<&IDataObject>, callback: F) -> Option<HDROP>
+  where
+    F: Fn(PathBuf),
+  {
+    let drop_format = Self::format(CF_HDROP.0);
 
     match data_obj
       .as_ref()
@@ -119,7 +162,7 @@ impl IDropTarget_Impl for FileDropHandler_Impl {
         });
       });
       let hovered_is_valid = hdrop.is_some();
-      let cursor_effect = if hovered_is_valid {
+      let cursor_effect = if hovered_is_valid || FileDropHandler::has_text(pDataObj) {
         DROPEFFECT_COPY
       } else {
         DROPEFFECT_NONE
@@ -171,6 +214,21 @@ impl IDropTarget_Impl for FileDropHandler_Impl {
       });
       if let Some(hdrop) = hdrop {
         DragFinish(hdrop);
+      } else {
+        use crate::event::WindowEvent::{DroppedText, DroppedUrl};
+
+        let url = FileDropHandler::get_text(pDataObj, FileDropHandler::url_format())
+          .and_then(|url| url::Url::parse(url.trim()).ok());
+        let event = match url {
+          Some(url) => Some(DroppedUrl(url)),
+          None => FileDropHandler::get_text(pDataObj, CF_UNICODETEXT.0).map(DroppedText),
+        };
+        if let Some(event) = event {
+          (self.send_event)(Event::WindowEvent {
+            window_id: SuperWindowId(WindowId(self.window.0 as _)),
+            event,
+          });
+        }
       }
     }
     Ok(())
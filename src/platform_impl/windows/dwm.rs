@@ -0,0 +1,86 @@
+// Copyright 2014-2021 The winit contributors
+// Copyright 2021-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+
+//! Window attributes of the Desktop Window Manager that only exist on Windows 11.
+
+use once_cell::sync::Lazy;
+use windows::Win32::{
+  Foundation::HWND,
+  Graphics::Dwm::{
+    DwmSetWindowAttribute, DWMWA_BORDER_COLOR, DWMWA_CAPTION_COLOR, DWMWA_TEXT_COLOR,
+    DWMWA_WINDOW_CORNER_PREFERENCE, DWMWCP_DEFAULT, DWMWCP_DONOTROUND, DWMWCP_ROUND,
+    DWMWCP_ROUNDSMALL, DWMWINDOWATTRIBUTE, DWM_WINDOW_CORNER_PREFERENCE,
+  },
+};
+
+use std::{ffi::c_void, mem};
+
+use crate::{platform::windows::CornerPreference, window::RGBA};
+
+/// Resets a color attribute to the system default.
+const DWMWA_COLOR_DEFAULT: u32 = 0xFFFFFFFF;
+
+/// The colors of the window frame that can be changed.
+#[derive(Debug, Clone, Copy)]
+pub enum FrameColor {
+  Border,
+  TitleBar,
+  TitleText,
+}
+
+static IS_WIN11: Lazy<bool> = Lazy::new(|| {
+  let version = windows_version::OsVersion::current();
+  version.major == 10 && version.build >= 22000
+});
+
+/// Sets how the corners of `hwnd` are rounded, returning whether it could be applied.
+pub fn set_corner_preference(hwnd: HWND, preference: CornerPreference) -> bool {
+  if !*IS_WIN11 {
+    return false;
+  }
+
+  let preference: DWM_WINDOW_CORNER_PREFERENCE = match preference {
+    CornerPreference::Default => DWMWCP_DEFAULT,
+    CornerPreference::DoNotRound => DWMWCP_DONOTROUND,
+    CornerPreference::Round => DWMWCP_ROUND,
+    CornerPreference::RoundSmall => DWMWCP_ROUNDSMALL,
+  };
+  unsafe {
+    DwmSetWindowAttribute(
+      hwnd,
+      DWMWA_WINDOW_CORNER_PREFERENCE,
+      &preference as *const _ as *const c_void,
+      mem::size_of::<DWM_WINDOW_CORNER_PREFERENCE>() as u32,
+    )
+  }
+  .is_ok()
+}
+
+/// Sets one of the frame colors of `hwnd`, or resets it to the default when `color` is `None`.
+/// The alpha component is ignored. Returns whether it could be applied.
+pub fn set_frame_color(hwnd: HWND, frame: FrameColor, color: Option<RGBA>) -> bool {
+  if !*IS_WIN11 {
+    return false;
+  }
+
+  let attribute: DWMWINDOWATTRIBUTE = match frame {
+    FrameColor::Border => DWMWA_BORDER_COLOR,
+    FrameColor::TitleBar => DWMWA_CAPTION_COLOR,
+    FrameColor::TitleText => DWMWA_TEXT_COLOR,
+  };
+  // `COLORREF` is laid out as 0x00BBGGRR
+  let color = match color {
+    Some((r, g, b, _)) => u32::from(r) | (u32::from(g) << 8) | (u32::from(b) << 16),
+    None => DWMWA_COLOR_DEFAULT,
+  };
+  unsafe {
+    DwmSetWindowAttribute(
+      hwnd,
+      attribute,
+      &color as *const u32 as *const c_void,
+      mem::size_of::<u32>() as u32,
+    )
+  }
+  .is_ok()
+}
@@ -9,7 +9,7 @@ mod runner;
 use crossbeam_channel::{self as channel, Receiver, Sender};
 use parking_lot::Mutex;
 use std::{
-  cell::Cell,
+  cell::{Cell, RefCell},
   collections::VecDeque,
   ffi::c_void,
   marker::PhantomData,
@@ -23,10 +23,11 @@ use windows::{
   core::{s, PCWSTR},
   Win32::{
     Foundation::{
-      BOOL, HANDLE, HINSTANCE, HMODULE, HWND, LPARAM, LRESULT, POINT, RECT, WAIT_TIMEOUT, WPARAM,
+      BOOL, HANDLE, HINSTANCE, HWND, LPARAM, LRESULT, POINT, RECT, WAIT_TIMEOUT, WPARAM,
     },
     Graphics::Gdi::*,
     System::{
+      DataExchange::COPYDATASTRUCT,
       LibraryLoader::GetModuleHandleW,
       Ole::{IDropTarget, RevokeDragDrop},
       Threading::{GetCurrentThreadId, INFINITE},
@@ -36,7 +37,7 @@ use windows::{
       Input::{KeyboardAndMouse::*, Pointer::*, Touch::*, *},
       Shell::{
         DefSubclassProc, RemoveWindowSubclass, SHAppBarMessage, SetWindowSubclass, ABE_BOTTOM,
-        ABE_LEFT, ABE_RIGHT, ABE_TOP, ABM_GETAUTOHIDEBAR, APPBARDATA,
+        ABE_LEFT, ABE_RIGHT, ABE_TOP, ABM_GETAUTOHIDEBAREX, APPBARDATA,
       },
       WindowsAndMessaging::{self as win32wm, *},
     },
@@ -51,21 +52,25 @@ use crate::{
   keyboard::{KeyCode, ModifiersState},
   monitor::MonitorHandle as RootMonitorHandle,
   platform_impl::platform::{
-    dark_mode::try_window_theme,
+    cursor,
+    dark_mode::{self, try_window_theme},
     dpi::{become_dpi_aware, dpi_to_scale_factor, enable_non_client_dpi_scaling},
     keyboard::is_msg_keyboard_related,
     keyboard_layout::LAYOUT_CACHE,
     minimal_ime::is_msg_ime_related,
     monitor::{self, MonitorHandle},
     raw_input, util,
-    window::set_skip_taskbar,
+    window::{register_drop_target, set_skip_taskbar},
     window_state::{CursorFlags, WindowFlags, WindowState},
     wrap_device_id, WindowId, DEVICE_ID,
   },
-  window::{Fullscreen, Theme, WindowId as RootWindowId},
+  window::{CursorIcon, Fullscreen, HitTestResult, Theme, WindowId as RootWindowId, WindowMode},
 };
 use runner::{EventLoopRunner, EventLoopRunnerShared};
 
+#[cfg(feature = "test-util")]
+use crate::platform::pump_events::PumpStatus;
+
 type GetPointerFrameInfoHistory = unsafe extern "system" fn(
   pointerId: u32,
   entriesCount: *mut u32,
@@ -103,7 +108,7 @@ lazy_static! {
 pub(crate) struct SubclassInput<T: 'static> {
   pub window_state: Arc<Mutex<WindowState>>,
   pub event_loop_runner: EventLoopRunnerShared<T>,
-  pub _file_drop_handler: Option<IDropTarget>,
+  pub file_drop_handler: RefCell<Option<IDropTarget>>,
   pub subclass_removed: Cell<bool>,
   pub recurse_depth: Cell<u32>,
   pub event_loop_preferred_theme: Arc<Mutex<Option<Theme>>>,
@@ -204,6 +209,11 @@ impl<T: 'static> EventLoop<T> {
           runner_shared,
           preferred_theme: Arc::new(Mutex::new(attributes.preferred_theme)),
         },
+        control_flow: Default::default(),
+        busy_cursor_depth: Default::default(),
+        resize_debouncer: Default::default(),
+        #[cfg(feature = "trace")]
+        stats: Default::default(),
         _marker: PhantomData,
       },
       msg_hook: attributes.msg_hook.take(),
@@ -276,9 +286,100 @@ impl<T: 'static> EventLoop<T> {
       runner.loop_destroyed();
     }
     runner.reset_runner();
+    monitor::restore_display_modes();
     exit_code
   }
 
+  /// Dispatches the messages of a single event loop iteration, waiting for up to `timeout` for
+  /// one to arrive first.
+  #[cfg(feature = "test-util")]
+  pub fn pump_events<F>(&mut self, timeout: Option<Duration>, mut event_handler: F) -> PumpStatus
+  where
+    F: FnMut(Event<'_, T>, &RootELW<T>, &mut ControlFlow),
+  {
+    let event_loop_windows_ref = &self.window_target;
+
+    unsafe {
+      self
+        .window_target
+        .p
+        .runner_shared
+        .set_event_handler(move |event, control_flow| {
+          event_handler(event, event_loop_windows_ref, control_flow);
+        });
+    }
+
+    let runner = &self.window_target.p.runner_shared;
+
+    let exit_code = unsafe {
+      let mut msg = MSG::default();
+
+      if !PeekMessageW(&mut msg, HWND::default(), 0, 0, PM_NOREMOVE).as_bool() {
+        let _ = MsgWaitForMultipleObjectsEx(
+          None,
+          timeout.map(dur2timeout).unwrap_or(INFINITE),
+          QS_ALLEVENTS,
+          MWMO_INPUTAVAILABLE,
+        );
+      }
+
+      // This sends `NewEvents` and invalidates the thread event target, whose `WM_PAINT` ends the
+      // iteration with `MainEventsCleared` and `RedrawEventsCleared` once the queue is empty.
+      runner.poll();
+
+      let mut exit_code = None;
+      while runner.handling_events() {
+        if let Err(payload) = runner.take_panic_error() {
+          runner.reset_runner();
+          panic::resume_unwind(payload);
+        }
+
+        if !PeekMessageW(&mut msg, HWND::default(), 0, 0, PM_REMOVE).as_bool() {
+          break;
+        }
+        if msg.message == WM_QUIT {
+          exit_code = Some(0);
+          break;
+        }
+
+        let handled = if let Some(callback) = self.msg_hook.as_deref_mut() {
+          callback(&mut msg as *mut _ as *mut _)
+        } else {
+          false
+        };
+        if !handled {
+          let _ = TranslateMessage(&msg);
+          DispatchMessageW(&msg);
+        }
+      }
+
+      if let Err(payload) = runner.take_panic_error() {
+        runner.reset_runner();
+        panic::resume_unwind(payload);
+      }
+
+      match runner.control_flow() {
+        ControlFlow::ExitWithCode(code) => Some(code),
+        _ => exit_code,
+      }
+    };
+
+    match exit_code {
+      Some(code) => {
+        unsafe {
+          runner.loop_destroyed();
+        }
+        runner.reset_runner();
+        monitor::restore_display_modes();
+        PumpStatus::Exit(code)
+      }
+      None => {
+        runner.clear_event_handler();
+        PumpStatus::Continue
+      }
+    }
+  }
+
   pub fn create_proxy(&self) -> EventLoopProxy<T> {
     EventLoopProxy {
       target_window: self.window_target.p.thread_msg_target,
@@ -306,10 +407,28 @@ impl<T> EventLoopWindowTarget<T> {
     Some(RootMonitorHandle { inner: monitor })
   }
 
+  pub fn current_monitor(&self) -> Option<RootMonitorHandle> {
+    let foreground = unsafe { GetForegroundWindow() };
+    let monitor = if foreground.is_invalid() {
+      monitor::primary_monitor()
+    } else {
+      monitor::current_monitor(foreground)
+    };
+    Some(RootMonitorHandle { inner: monitor })
+  }
+
   pub fn monitor_from_point(&self, x: f64, y: f64) -> Option<MonitorHandle> {
     monitor::from_point(x, y)
   }
 
+  pub fn wheel_scroll_lines(&self) -> u32 {
+    util::wheel_scroll_lines()
+  }
+
+  pub fn wheel_scroll_chars(&self) -> u32 {
+    util::wheel_scroll_chars()
+  }
+
   #[cfg(feature = "rwh_05")]
   pub fn raw_display_handle_rwh_05(&self) -> rwh_05::RawDisplayHandle {
     rwh_05::RawDisplayHandle::Windows(rwh_05::WindowsDisplayHandle::empty())
@@ -331,6 +450,16 @@ impl<T> EventLoopWindowTarget<T> {
     util::cursor_position().map_err(Into::into)
   }
 
+  #[inline]
+  pub fn set_busy_cursor(&self, busy: bool) {
+    cursor::set_busy(busy);
+  }
+
+  #[inline]
+  pub fn theme(&self) -> Theme {
+    dark_mode::resolve_theme(*self.preferred_theme.lock())
+  }
+
   #[inline]
   pub fn set_theme(&self, theme: Option<Theme>) {
     *self.preferred_theme.lock() = theme;
@@ -477,6 +606,8 @@ fn dur2timeout(dur: Duration) -> u32 {
 
 impl<T> Drop for EventLoop<T> {
   fn drop(&mut self) {
+    // also runs when unwinding from a panic in the event handler
+    monitor::restore_display_modes();
     unsafe {
       let _ = DestroyWindow(self.window_target.p.thread_msg_target);
     }
@@ -624,6 +755,11 @@ lazy_static! {
     pub static ref SET_RETAIN_STATE_ON_SIZE_MSG_ID: u32 = unsafe {
         RegisterWindowMessageA(s!("Tao::SetRetainMaximized"))
     };
+    /// Message sent by a `Window` to register or revoke its drop target.
+    /// WPARAM is a bool specifying whether drag and drop is enabled. LPARAM is unused.
+    pub static ref SET_DRAG_AND_DROP_MSG_ID: u32 = unsafe {
+        RegisterWindowMessageA(s!("Tao::SetDragAndDrop"))
+    };
     /// Message sent by event loop when event loop's prefered theme changed.
     /// WPARAM and LPARAM are unused.
     pub static ref CHANGE_THEME_MSG_ID: u32 = unsafe {
@@ -984,11 +1120,19 @@ unsafe fn public_window_callback_inner<T: 'static>(
       // when not appropriate.
       return;
     }
+    let intercept_alt_f4 = subclass_input.window_state.lock().intercept_alt_f4;
     let events = {
       let mut key_event_builders =
         crate::platform_impl::platform::keyboard::KEY_EVENT_BUILDERS.lock();
       if let Some(key_event_builder) = key_event_builders.get_mut(&WindowId(window.0 as _)) {
-        key_event_builder.process_message(window, msg, wparam, lparam, &mut result)
+        key_event_builder.process_message(
+          window,
+          msg,
+          wparam,
+          lparam,
+          intercept_alt_f4,
+          &mut result,
+        )
       } else {
         Vec::new()
       }
@@ -1017,6 +1161,9 @@ unsafe fn public_window_callback_inner<T: 'static>(
     }
     let text = {
       let mut window_state = subclass_input.window_state.lock();
+      if !window_state.ime_allowed {
+        return;
+      }
       window_state
         .ime_handler
         .process_message(window, msg, wparam, lparam, &mut result)
@@ -1079,6 +1226,21 @@ unsafe fn public_window_callback_inner<T: 'static>(
       result = ProcResult::Value(LRESULT(0));
     }
 
+    win32wm::WM_COPYDATA => {
+      let copy_data = &*(lparam.0 as *const COPYDATASTRUCT);
+      let payload = if copy_data.lpData.is_null() || copy_data.cbData == 0 {
+        &[][..]
+      } else {
+        std::slice::from_raw_parts(copy_data.lpData as *const u8, copy_data.cbData as usize)
+      };
+      if subclass_input
+        .event_loop_runner
+        .handle_copy_data(copy_data.dwData, payload)
+      {
+        result = ProcResult::Value(LRESULT(1));
+      }
+    }
+
     win32wm::WM_DESTROY => {
       use crate::event::WindowEvent::Destroyed;
       let _ = RevokeDragDrop(window);
@@ -1157,8 +1319,10 @@ unsafe fn public_window_callback_inner<T: 'static>(
 
         if let Some(new_rect) = new_rect {
           let new_monitor = MonitorFromRect(&new_rect, MONITOR_DEFAULTTONULL);
+          let is_work_area = matches!(fullscreen, Fullscreen::BorderlessWorkArea(_));
           match fullscreen {
-            Fullscreen::Borderless(ref mut fullscreen_monitor) => {
+            Fullscreen::Borderless(ref mut fullscreen_monitor)
+            | Fullscreen::BorderlessWorkArea(ref mut fullscreen_monitor) => {
               if !new_monitor.is_invalid()
                 && fullscreen_monitor
                   .as_ref()
@@ -1166,7 +1330,11 @@ unsafe fn public_window_callback_inner<T: 'static>(
                   .unwrap_or(true)
               {
                 if let Ok(new_monitor_info) = monitor::get_monitor_info(new_monitor) {
-                  let new_monitor_rect = new_monitor_info.monitorInfo.rcMonitor;
+                  let new_monitor_rect = if is_work_area {
+                    new_monitor_info.monitorInfo.rcWork
+                  } else {
+                    new_monitor_info.monitorInfo.rcMonitor
+                  };
                   window_pos.x = new_monitor_rect.left;
                   window_pos.y = new_monitor_rect.top;
                   window_pos.cx = new_monitor_rect.right - new_monitor_rect.left;
@@ -1228,7 +1396,14 @@ unsafe fn public_window_callback_inner<T: 'static>(
         event: Resized(physical_size),
       };
 
-      {
+      let minimized = match wparam.0 as u32 {
+        win32wm::SIZE_MINIMIZED => Some(true),
+        win32wm::SIZE_RESTORED | win32wm::SIZE_MAXIMIZED => Some(false),
+        // SIZE_MAXSHOW and SIZE_MAXHIDE are about other windows
+        _ => None,
+      };
+
+      let (minimized_changed, window_mode_changed) = {
         let mut w = subclass_input.window_state.lock();
         // See WindowFlags::MARKER_RETAIN_STATE_ON_SIZE docs for info on why this `if` check exists.
         if !w
@@ -1237,10 +1412,63 @@ unsafe fn public_window_callback_inner<T: 'static>(
         {
           let maximized = wparam.0 == win32wm::SIZE_MAXIMIZED as _;
           w.set_window_flags_in_place(|f| f.set(WindowFlags::MAXIMIZED, maximized));
+          // The window was restored, possibly by the user or the system (e.g. double-clicking
+          // the caption or `Win+Down`), so the bounds saved by `set_maximized` are stale.
+          if wparam.0 == win32wm::SIZE_RESTORED as _ {
+            w.saved_maximized_window = None;
+          }
         }
+
+        let minimized_changed = match minimized {
+          Some(minimized) if minimized != w.minimized => {
+            w.minimized = minimized;
+            Some(minimized)
+          }
+          _ => None,
+        };
+
+        let window_mode = match minimized {
+          Some(true) => Some(WindowMode::Minimized),
+          Some(false) if w.fullscreen.is_some() => Some(WindowMode::Fullscreen),
+          Some(false) if w.window_flags().contains(WindowFlags::MAXIMIZED) => {
+            Some(WindowMode::Maximized)
+          }
+          Some(false) => Some(WindowMode::Normal),
+          None => None,
+        };
+        let window_mode_changed = match window_mode {
+          Some(window_mode) if window_mode != w.window_mode => {
+            w.window_mode = window_mode;
+            Some(window_mode)
+          }
+          _ => None,
+        };
+
+        (minimized_changed, window_mode_changed)
+      };
+
+      // Minimizing reports a 0x0 client area, which renderers can't create surfaces for.
+      if minimized != Some(true) {
+        subclass_input.send_event(event);
       }
 
-      subclass_input.send_event(event);
+      if let Some(minimized) = minimized_changed {
+        subclass_input.send_event(Event::WindowEvent {
+          window_id: RootWindowId(WindowId(window.0 as _)),
+          event: WindowEvent::Minimized(minimized),
+        });
+        subclass_input.send_event(Event::WindowEvent {
+          window_id: RootWindowId(WindowId(window.0 as _)),
+          event: WindowEvent::Occluded(minimized),
+        });
+      }
+
+      if let Some(window_mode) = window_mode_changed {
+        subclass_input.send_event(Event::WindowEvent {
+          window_id: RootWindowId(WindowId(window.0 as _)),
+          event: WindowEvent::WindowModeChanged(window_mode),
+        });
+      }
       result = ProcResult::Value(LRESULT(0));
     }
 
@@ -1254,7 +1482,7 @@ unsafe fn public_window_callback_inner<T: 'static>(
         let mut w = subclass_input.window_state.lock();
         w.set_window_flags_in_place(|f| f.set(WindowFlags::MINIMIZED, true));
       }
-      // Send `WindowEvent::Minimized` here if we decide to implement one
+      // `WindowEvent::Minimized` is sent from `WM_SIZE`, which also covers `ShowWindow` calls
 
       if wparam.0 == SC_SCREENSAVE as _ {
         let window_state = subclass_input.window_state.lock();
@@ -1386,7 +1614,12 @@ unsafe fn public_window_callback_inner<T: 'static>(
 
     win32wm::WM_KEYDOWN | win32wm::WM_SYSKEYDOWN => {
       if msg == WM_SYSKEYDOWN && wparam.0 == usize::from(VK_F4.0) {
-        result = ProcResult::DefSubclassProc;
+        // Leaving Alt+F4 to the system makes it close the window.
+        result = if subclass_input.window_state.lock().intercept_alt_f4 {
+          ProcResult::Value(LRESULT(0))
+        } else {
+          ProcResult::DefSubclassProc
+        };
       }
     }
 
@@ -1757,7 +1990,19 @@ unsafe fn public_window_callback_inner<T: 'static>(
     }
 
     win32wm::WM_SETFOCUS => {
-      let active_focus_changed = subclass_input.window_state.lock().set_focused(true);
+      let active_focus_changed = {
+        let mut window_state = subclass_input.window_state.lock();
+        // The OS resets the cursor clip when the window loses focus, so confine
+        // the cursor again if it was grabbed.
+        if window_state
+          .mouse
+          .cursor_flags()
+          .contains(CursorFlags::GRABBED)
+        {
+          let _ = window_state.mouse.refresh_os_cursor(window);
+        }
+        window_state.set_focused(true)
+      };
       if active_focus_changed {
         gain_active_focus(window, subclass_input);
       }
@@ -1765,7 +2010,19 @@ unsafe fn public_window_callback_inner<T: 'static>(
     }
 
     win32wm::WM_KILLFOCUS => {
-      let active_focus_changed = subclass_input.window_state.lock().set_focused(false);
+      let active_focus_changed = {
+        let mut window_state = subclass_input.window_state.lock();
+        // Don't keep the cursor confined while another window has the focus,
+        // it is grabbed again in `WM_SETFOCUS`.
+        if window_state
+          .mouse
+          .cursor_flags()
+          .contains(CursorFlags::GRABBED)
+        {
+          let _ = util::set_cursor_clip(None);
+        }
+        window_state.set_focused(false)
+      };
       if active_focus_changed {
         lose_active_focus(window, subclass_input);
       }
@@ -1779,7 +2036,9 @@ unsafe fn public_window_callback_inner<T: 'static>(
         // provided through the low-order word of lParam. We use that here since
         // `WM_MOUSEMOVE` seems to come after `WM_SETCURSOR` for a given cursor movement.
         let in_client_area = u32::from(util::LOWORD(lparam.0 as u32)) == HTCLIENT;
-        if in_client_area {
+        if cursor::is_busy() {
+          Some(CursorIcon::Wait)
+        } else if in_client_area {
           Some(window_state.mouse.cursor)
         } else {
           None
@@ -1788,7 +2047,7 @@ unsafe fn public_window_callback_inner<T: 'static>(
 
       match set_cursor_to {
         Some(cursor) => {
-          if let Ok(cursor) = LoadCursorW(HMODULE::default(), cursor.to_windows_cursor()) {
+          if let Some(cursor) = cursor::load_cursor(cursor) {
             SetCursor(cursor);
           }
           result = ProcResult::Value(LRESULT(0));
@@ -1859,11 +2118,14 @@ unsafe fn public_window_callback_inner<T: 'static>(
       // application since they are the same".
       // https://msdn.microsoft.com/en-us/library/windows/desktop/dn312083(v=vs.85).aspx
       let new_dpi_x = u32::from(util::LOWORD(wparam.0 as u32));
-      let new_scale_factor = dpi_to_scale_factor(new_dpi_x);
+      let new_scale_factor: f64;
       let old_scale_factor: f64;
 
       let (allow_resize, is_decorated) = {
         let mut window_state = subclass_input.window_state.lock();
+        new_scale_factor = window_state
+          .forced_scale_factor
+          .unwrap_or_else(|| dpi_to_scale_factor(new_dpi_x));
         old_scale_factor = window_state.scale_factor;
         window_state.scale_factor = new_scale_factor;
 
@@ -1955,12 +2217,13 @@ unsafe fn public_window_callback_inner<T: 'static>(
       let dragging_window: bool;
 
       {
-        let window_state = subclass_input.window_state.lock();
+        let mut window_state = subclass_input.window_state.lock();
         dragging_window = window_state
           .window_flags()
           .contains(WindowFlags::MARKER_IN_SIZE_MOVE);
         // Unset maximized if we're changing the window's size.
         if new_physical_inner_size != old_physical_inner_size {
+          window_state.saved_maximized_window = None;
           WindowState::set_window_flags(window_state, window, |f| {
             f.set(WindowFlags::MAXIMIZED, false)
           });
@@ -2073,6 +2336,20 @@ unsafe fn public_window_callback_inner<T: 'static>(
         SWP_NOZORDER | SWP_NOACTIVATE,
       );
 
+      // `WM_SIZE` emits the `Resized` event, but it isn't sent if the size didn't change
+      let mut rect = RECT::default();
+      let _ = GetClientRect(window, &mut rect);
+      let physical_size = PhysicalSize::new(
+        (rect.right - rect.left) as u32,
+        (rect.bottom - rect.top) as u32,
+      );
+      if physical_size == old_physical_inner_size {
+        subclass_input.send_event(Event::WindowEvent {
+          window_id: RootWindowId(WindowId(window.0 as _)),
+          event: WindowEvent::Resized(physical_size),
+        });
+      }
+
       result = ProcResult::Value(LRESULT(0));
     }
 
@@ -2094,32 +2371,28 @@ unsafe fn public_window_callback_inner<T: 'static>(
           {
             let mut rect = monitor_info.monitorInfo.rcWork;
 
-            let mut edges = 0;
+            // keep a 1px gap on the edges of this monitor that have an auto-hide taskbar,
+            // otherwise the taskbar can't pop up above the window.
+            // `ABE_*` are edge indices, not flags, and `ABM_GETAUTOHIDEBAR` only looks at the
+            // primary monitor, so each edge of the window's monitor is queried separately.
             for edge in [ABE_BOTTOM, ABE_LEFT, ABE_TOP, ABE_RIGHT] {
               let mut app_data = APPBARDATA {
                 cbSize: std::mem::size_of::<APPBARDATA>() as _,
                 uEdge: edge,
+                rc: monitor_info.monitorInfo.rcMonitor,
                 ..Default::default()
               };
-              if SHAppBarMessage(ABM_GETAUTOHIDEBAR, &mut app_data) != 0 {
-                edges |= edge;
+              if SHAppBarMessage(ABM_GETAUTOHIDEBAREX, &mut app_data) != 0 {
+                match edge {
+                  ABE_BOTTOM => rect.bottom -= 1,
+                  ABE_LEFT => rect.left += 1,
+                  ABE_TOP => rect.top += 1,
+                  ABE_RIGHT => rect.right -= 1,
+                  _ => (),
+                }
               }
             }
 
-            // keep a 1px for taskbar auto-hide to work
-            if edges & ABE_BOTTOM != 0 {
-              rect.bottom -= 1;
-            }
-            if edges & ABE_LEFT != 0 {
-              rect.left += 1;
-            }
-            if edges & ABE_TOP != 0 {
-              rect.top += 1;
-            }
-            if edges & ABE_RIGHT != 0 {
-              rect.right -= 1;
-            }
-
             params.rgrc[0] = rect;
           }
         } else if window_flags.contains(WindowFlags::MARKER_UNDECORATED_SHADOW) {
@@ -2150,9 +2423,19 @@ unsafe fn public_window_callback_inner<T: 'static>(
         let mut rect = RECT::default();
         let _ = GetWindowRect(window, &mut rect);
 
-        let padded_border = GetSystemMetrics(SM_CXPADDEDBORDER);
-        let border_x = GetSystemMetrics(SM_CXFRAME) + padded_border;
-        let border_y = GetSystemMetrics(SM_CYFRAME) + padded_border;
+        let (border_x, border_y) = match window_state.resize_border_thickness {
+          Some(thickness) => {
+            let border = (thickness * window_state.scale_factor).round() as i32;
+            (border, border)
+          }
+          None => {
+            let padded_border = GetSystemMetrics(SM_CXPADDEDBORDER);
+            (
+              GetSystemMetrics(SM_CXFRAME) + padded_border,
+              GetSystemMetrics(SM_CYFRAME) + padded_border,
+            )
+          }
+        };
 
         let hit_result = crate::window::hit_test(
           (rect.left, rect.top, rect.right, rect.bottom),
@@ -2169,6 +2452,57 @@ unsafe fn public_window_callback_inner<T: 'static>(
       } else {
         result = ProcResult::DefSubclassProc;
       }
+
+      // The drag region acts as a title bar
+      let drag_region = window_state
+        .drag_region
+        .filter(|_| matches!(result, ProcResult::DefSubclassProc));
+      if let Some((position, size)) = drag_region {
+        let position: PhysicalPosition<i32> = position.to_physical(window_state.scale_factor);
+        let size: PhysicalSize<i32> = size.to_physical(window_state.scale_factor);
+        let mut point = POINT {
+          x: util::GET_X_LPARAM(lparam) as i32,
+          y: util::GET_Y_LPARAM(lparam) as i32,
+        };
+        let _ = ScreenToClient(window, &mut point);
+        if point.x >= position.x
+          && point.x < position.x + size.width
+          && point.y >= position.y
+          && point.y < position.y + size.height
+        {
+          result = ProcResult::Value(LRESULT(HTCAPTION as _));
+        }
+      }
+
+      // The hit test handler replaces both of the above inside the client area. It is called
+      // with the state unlocked since it may use the window.
+      let handler = window_state.hit_test_handler.clone();
+      let can_resize =
+        window_flags.contains(WindowFlags::RESIZABLE) && window_state.fullscreen.is_none();
+      drop(window_state);
+      if let Some(handler) = handler {
+        let mut point = POINT {
+          x: util::GET_X_LPARAM(lparam) as i32,
+          y: util::GET_Y_LPARAM(lparam) as i32,
+        };
+        let _ = ScreenToClient(window, &mut point);
+        let mut rect = RECT::default();
+        let _ = GetClientRect(window, &mut rect);
+        if point.x >= rect.left
+          && point.x < rect.right
+          && point.y >= rect.top
+          && point.y < rect.bottom
+        {
+          let can_resize = can_resize && !util::is_maximized(window).unwrap_or(false);
+          let hit_result = match handler(PhysicalPosition::new(point.x as f64, point.y as f64)) {
+            HitTestResult::Client => HTCLIENT,
+            HitTestResult::Drag => HTCAPTION,
+            HitTestResult::Resize(direction) if can_resize => direction.to_win32(),
+            HitTestResult::Resize(_) => HTCLIENT,
+          };
+          result = ProcResult::Value(LRESULT(hit_result as _));
+        }
+      }
     }
 
     win32wm::WM_SYSCHAR => {
@@ -2186,7 +2520,33 @@ unsafe fn public_window_callback_inner<T: 'static>(
           f.set(WindowFlags::MARKER_RETAIN_STATE_ON_SIZE, wparam.0 != 0)
         });
         result = ProcResult::Value(LRESULT(0));
+      } else if msg == *SET_DRAG_AND_DROP_MSG_ID {
+        let mut file_drop_handler = subclass_input.file_drop_handler.borrow_mut();
+        if wparam.0 != 0 {
+          if file_drop_handler.is_none() {
+            let file_drop_runner = subclass_input.event_loop_runner.clone();
+            *file_drop_handler = Some(register_drop_target(
+              window,
+              Box::new(move |event| {
+                if let Ok(e) = event.map_nonuser_event() {
+                  file_drop_runner.send_event(e)
+                }
+              }),
+            ));
+          }
+        } else if file_drop_handler.take().is_some() {
+          let _ = RevokeDragDrop(window);
+        }
+        result = ProcResult::Value(LRESULT(0));
       } else if msg == *CHANGE_THEME_MSG_ID {
+        // A non-zero `wparam` means the theme is inherited from the owner or parent window.
+        if wparam.0 != 0 {
+          subclass_input.window_state.lock().inherited_theme = match lparam.0 {
+            1 => Some(Theme::Light),
+            2 => Some(Theme::Dark),
+            _ => None,
+          };
+        }
         update_theme(subclass_input, window, false);
         result = ProcResult::Value(LRESULT(0));
       } else if msg == *S_U_TASKBAR_RESTART {
@@ -2214,16 +2574,23 @@ fn update_theme<T>(
   from_settings_change_event: bool,
 ) {
   let mut window_state = subclass_input.window_state.lock();
-  let preferred_theme = window_state
+  let own_theme = window_state
     .preferred_theme
-    .or(*subclass_input.event_loop_preferred_theme.lock());
+    .or(window_state.inherited_theme);
+  let preferred_theme = own_theme.or(*subclass_input.event_loop_preferred_theme.lock());
   if from_settings_change_event && preferred_theme.is_some() {
     return;
   }
   let new_theme = try_window_theme(window, preferred_theme, !from_settings_change_event);
-  if window_state.current_theme != new_theme {
-    window_state.current_theme = new_theme;
-    mem::drop(window_state);
+  let changed = window_state.current_theme != new_theme;
+  window_state.current_theme = new_theme;
+  mem::drop(window_state);
+
+  if !from_settings_change_event {
+    propagate_theme(window, own_theme);
+  }
+
+  if changed {
     unsafe {
       subclass_input.send_event(Event::WindowEvent {
         window_id: RootWindowId(WindowId(window.0 as _)),
@@ -2233,6 +2600,18 @@ fn update_theme<T>(
   }
 }
 
+/// Lets the windows owned by or parented to `window` inherit its theme.
+fn propagate_theme(window: HWND, theme: Option<Theme>) {
+  let lparam = LPARAM(match theme {
+    None => 0,
+    Some(Theme::Light) => 1,
+    Some(Theme::Dark) => 2,
+  });
+  for dependent in util::dependent_windows(window) {
+    unsafe { SendMessageW(dependent, *CHANGE_THEME_MSG_ID, WPARAM(1), lparam) };
+  }
+}
+
 fn is_show_window_contents_while_dragging_enabled() -> bool {
   let mut is_enabled: BOOL = BOOL(0);
   let result = unsafe {
@@ -2321,6 +2700,22 @@ unsafe extern "system" fn thread_event_target_callback<T: 'static>(
       DefSubclassProc(window, msg, wparam, lparam)
     }
 
+    // The thread event target is a top-level window, so it receives these once per event loop
+    win32wm::WM_QUERYENDSESSION => {
+      subclass_input.send_event(Event::SessionEnd { ending: false });
+      let _ = RedrawWindow(window, None, HRGN::default(), RDW_INTERNALPAINT);
+
+      // A shutdown block reason only shows why the session can't end, it still has to be denied.
+      LRESULT(!util::has_shutdown_block_reason() as _)
+    }
+
+    win32wm::WM_ENDSESSION => {
+      if wparam.0 != 0 {
+        subclass_input.send_event(Event::SessionEnd { ending: true });
+      }
+      LRESULT(0)
+    }
+
     _ if msg == *USER_EVENT_MSG_ID => {
       if let Ok(event) = subclass_input.user_event_receiver.recv() {
         subclass_input.send_event(Event::UserEvent(event));
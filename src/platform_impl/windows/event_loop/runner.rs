@@ -39,11 +39,16 @@ pub(crate) struct EventLoopRunner<T: 'static> {
 
   owned_windows: Cell<HashSet<isize>>,
 
+  copy_data_handler: Cell<Option<Box<CopyDataHandler<T>>>>,
+
   panic_error: Cell<Option<PanicError>>,
 }
 
 pub type PanicError = Box<dyn Any + Send + 'static>;
 
+/// Maps a `WM_COPYDATA` payload (`dwData` and the copied bytes) to a user event.
+pub(crate) type CopyDataHandler<T> = dyn FnMut(usize, &[u8]) -> Option<T>;
+
 /// See `move_state_to` function for details on how the state loop works.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum RunnerState {
@@ -78,6 +83,7 @@ impl<T> EventLoopRunner<T> {
       event_handler: Cell::new(None),
       event_buffer: RefCell::new(VecDeque::new()),
       owned_windows: Cell::new(HashSet::new()),
+      copy_data_handler: Cell::new(None),
     }
   }
 
@@ -103,12 +109,20 @@ impl<T> EventLoopRunner<T> {
       event_handler,
       event_buffer: _,
       owned_windows: _,
+      copy_data_handler: _,
     } = self;
     runner_state.set(RunnerState::Uninitialized);
     panic_error.set(None);
     control_flow.set(ControlFlow::Poll);
     event_handler.set(None);
   }
+
+  /// Drops the event handler but keeps the runner state, so the event loop can be resumed with a
+  /// new handler.
+  #[cfg(feature = "test-util")]
+  pub(crate) fn clear_event_handler(&self) {
+    self.event_handler.set(None);
+  }
 }
 
 /// State retrieval functions.
@@ -226,6 +240,26 @@ impl<T> EventLoopRunner<T> {
     }
   }
 
+  pub(crate) fn set_copy_data_handler(&self, handler: Option<Box<CopyDataHandler<T>>>) {
+    self.copy_data_handler.set(handler);
+  }
+
+  /// Runs the `WM_COPYDATA` handler and dispatches the event it returns, if any.
+  ///
+  /// Returns `false` if no handler is registered.
+  pub(crate) unsafe fn handle_copy_data(&self, data: usize, payload: &[u8]) -> bool {
+    let mut handler = match self.copy_data_handler.take() {
+      Some(handler) => handler,
+      None => return false,
+    };
+    let event = handler(data, payload);
+    self.copy_data_handler.set(Some(handler));
+    if let Some(event) = event {
+      self.send_event(Event::UserEvent(event));
+    }
+    true
+  }
+
   pub(crate) unsafe fn main_events_cleared(&self) {
     self.move_state_to(RunnerState::HandlingRedrawEvents);
   }
@@ -4,12 +4,14 @@
 
 use std::{fmt, io, iter::once, mem, os::windows::ffi::OsStrExt, path::Path, sync::Arc};
 
+use once_cell::sync::Lazy;
+
 use windows::{
   core::PCWSTR,
   Win32::{
     Foundation::{HMODULE, HWND, LPARAM, WPARAM},
     System::LibraryLoader::*,
-    UI::WindowsAndMessaging::*,
+    UI::{Shell::ExtractIconExW, WindowsAndMessaging::*},
   },
 };
 
@@ -22,26 +24,41 @@ impl Pixel {
 }
 
 impl RgbaIcon {
-  fn into_windows_icon(self) -> Result<WinIcon, BadIcon> {
+  /// Returns the pixels in the BGRA order `CreateIcon` expects, along with the matching AND mask.
+  fn into_bgra_and_mask(self) -> (Vec<u8>, Vec<u8>) {
     let mut rgba = self.rgba;
+    let width = self.width as usize;
     let pixel_count = rgba.len() / PIXEL_SIZE;
-    let mut and_mask = Vec::with_capacity(pixel_count);
+    // The AND mask is a monochrome bitmap with rows padded to a WORD boundary, a set bit marks
+    // a fully transparent pixel. Windows falls back to it when it can't use the alpha channel
+    // (e.g. when scaling the icon), so a wrong mask shows up as dark halos around the edges.
+    let mask_stride = (width + 15) / 16 * 2;
+    let mut and_mask = vec![0u8; mask_stride * self.height as usize];
     let pixels =
       unsafe { std::slice::from_raw_parts_mut(rgba.as_mut_ptr() as *mut Pixel, pixel_count) };
-    for pixel in pixels {
-      and_mask.push(pixel.a.wrapping_sub(std::u8::MAX)); // invert alpha channel
+    for (i, pixel) in pixels.iter_mut().enumerate() {
+      if pixel.a == 0 {
+        let (x, y) = (i % width, i / width);
+        and_mask[y * mask_stride + x / 8] |= 0x80 >> (x % 8);
+      }
+      // `HICON`s expect straight (non-premultiplied) alpha, so only the channel order changes.
       pixel.to_bgra();
     }
-    assert_eq!(and_mask.len(), pixel_count);
+    (rgba, and_mask)
+  }
+
+  fn into_windows_icon(self) -> Result<WinIcon, BadIcon> {
+    let (width, height) = (self.width, self.height);
+    let (bgra, and_mask) = self.into_bgra_and_mask();
     let handle = unsafe {
       CreateIcon(
         HMODULE::default(),
-        self.width as i32,
-        self.height as i32,
+        width as i32,
+        height as i32,
         1,
         (PIXEL_SIZE * 8) as u8,
         and_mask.as_ptr(),
-        rgba.as_ptr(),
+        bgra.as_ptr(),
       )
     };
     Ok(WinIcon::from_handle(
@@ -158,8 +175,57 @@ impl fmt::Debug for WinIcon {
   }
 }
 
+/// The first icon embedded in the executable, as `(big, small)` handles.
+///
+/// The window classes are registered without an icon, so this is what a window reverts to when
+/// its icon is unset. The handles are loaded once and kept alive for the whole process.
+static EXECUTABLE_ICONS: Lazy<(isize, isize)> = Lazy::new(|| {
+  let Ok(exe) = std::env::current_exe() else {
+    return (0, 0);
+  };
+  let path: Vec<u16> = exe.as_os_str().encode_wide().chain(once(0)).collect();
+  let (mut big, mut small) = (HICON::default(), HICON::default());
+  unsafe {
+    ExtractIconExW(
+      PCWSTR(path.as_ptr()),
+      0,
+      Some(&mut big),
+      Some(&mut small),
+      1,
+    )
+  };
+  (big.0 as isize, small.0 as isize)
+});
+
+/// Removes the icon set by tao, falling back to the icon of the executable if it has one.
 pub fn unset_for_window(hwnd: HWND, icon_type: IconType) {
+  let (big, small) = *EXECUTABLE_ICONS;
+  let handle = match icon_type {
+    IconType::Small => small,
+    IconType::Big => big,
+  };
   unsafe {
-    SendMessageW(hwnd, WM_SETICON, WPARAM(icon_type as _), LPARAM(0));
+    SendMessageW(hwnd, WM_SETICON, WPARAM(icon_type as _), LPARAM(handle));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn rgba_is_converted_to_bgra_with_an_and_mask() {
+    // a 2x2 icon with a fully transparent, a semi-transparent and two opaque pixels
+    let rgba = vec![0, 0, 0, 0, 255, 0, 0, 128, 0, 255, 0, 255, 0, 0, 255, 255];
+    let (bgra, and_mask) = RgbaIcon::from_rgba(rgba, 2, 2)
+      .unwrap()
+      .into_bgra_and_mask();
+
+    assert_eq!(
+      bgra,
+      [0, 0, 0, 0, 0, 0, 255, 128, 0, 255, 0, 255, 255, 0, 0, 255]
+    );
+    // rows are padded to 2 bytes, only the fully transparent pixel is masked
+    assert_eq!(and_mask, [0b1000_0000, 0, 0, 0]);
   }
 }
@@ -82,6 +82,7 @@ impl KeyEventBuilder {
     msg_kind: u32,
     wparam: WPARAM,
     lparam: LPARAM,
+    intercept_alt_f4: bool,
     result: &mut ProcResult,
   ) -> Vec<MessageAsKeyEvent> {
     match msg_kind {
@@ -102,8 +103,8 @@ impl KeyEventBuilder {
         }
       }
       win32wm::WM_KEYDOWN | win32wm::WM_SYSKEYDOWN => {
-        if msg_kind == WM_SYSKEYDOWN && wparam.0 == usize::from(VK_F4.0) {
-          // Don't dispatch Alt+F4 to the application.
+        if msg_kind == WM_SYSKEYDOWN && wparam.0 == usize::from(VK_F4.0) && !intercept_alt_f4 {
+          // Don't dispatch Alt+F4 to the application unless it asked to intercept it.
           // This is handled in `event_loop.rs`
           return vec![];
         }
@@ -10,6 +10,7 @@ use windows::Win32::{
 };
 
 pub(crate) use self::{
+  dwm::FrameColor,
   event_loop::{
     EventLoop, EventLoopProxy, EventLoopWindowTarget, PlatformSpecificEventLoopAttributes,
   },
@@ -145,9 +146,12 @@ impl WindowId {
 
 #[macro_use]
 mod util;
+mod blur;
+mod cursor;
 mod dark_mode;
 mod dpi;
 mod drop_handler;
+mod dwm;
 mod event_loop;
 mod icon;
 mod keyboard;
@@ -3,13 +3,21 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use windows::{
-  core::PCWSTR,
+  core::{Interface, PCWSTR},
   Win32::{
     Foundation::{BOOL, HWND, LPARAM, POINT, RECT},
-    Graphics::Gdi::*,
+    Graphics::{
+      Dxgi::{
+        Common::DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020, CreateDXGIFactory1, IDXGIFactory1,
+        IDXGIOutput6,
+      },
+      Gdi::*,
+    },
+    UI::WindowsAndMessaging::EDD_GET_DEVICE_INTERFACE_NAME,
   },
 };
 
+use parking_lot::Mutex;
 use std::{
   collections::{BTreeSet, VecDeque},
   io, mem,
@@ -18,7 +26,7 @@ use std::{
 use super::util;
 use crate::{
   dpi::{PhysicalPosition, PhysicalSize},
-  monitor::{MonitorHandle as RootMonitorHandle, VideoMode as RootVideoMode},
+  monitor::{HdrMetadata, MonitorHandle as RootMonitorHandle, VideoMode as RootVideoMode},
   platform_impl::platform::{
     dpi::{dpi_to_scale_factor, get_monitor_dpi},
     window::Window,
@@ -85,8 +93,46 @@ impl VideoMode {
   }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
-pub struct MonitorHandle(isize);
+#[derive(Debug, Clone)]
+pub struct MonitorHandle {
+  hmonitor: isize,
+  // `HMONITOR`s are handed out anew when the display configuration changes, so monitors are
+  // compared by the device interface path of the monitor, captured when the handle is created.
+  // Monitors whose path can't be queried fall back to the handle.
+  device_path: Option<String>,
+}
+
+impl MonitorHandle {
+  fn identity(&self) -> Result<&str, isize> {
+    self.device_path.as_deref().ok_or(self.hmonitor)
+  }
+}
+
+impl PartialEq for MonitorHandle {
+  fn eq(&self, other: &Self) -> bool {
+    self.identity() == other.identity()
+  }
+}
+
+impl Eq for MonitorHandle {}
+
+impl PartialOrd for MonitorHandle {
+  fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for MonitorHandle {
+  fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    self.identity().cmp(&other.identity())
+  }
+}
+
+impl std::hash::Hash for MonitorHandle {
+  fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    self.identity().hash(state);
+  }
+}
 
 unsafe extern "system" fn monitor_enum_proc(
   hmonitor: HMONITOR,
@@ -171,9 +217,74 @@ pub(crate) fn get_monitor_info(hmonitor: HMONITOR) -> Result<MONITORINFOEXW, io:
   }
 }
 
+/// Returns the device interface path of the monitor attached to `hmonitor`, which stays the same
+/// for as long as the monitor is connected to the same port.
+fn get_device_path(hmonitor: HMONITOR) -> Option<String> {
+  let monitor_info = get_monitor_info(hmonitor).ok()?;
+  let mut device = DISPLAY_DEVICEW {
+    cb: mem::size_of::<DISPLAY_DEVICEW>() as u32,
+    ..Default::default()
+  };
+  let found = unsafe {
+    EnumDisplayDevicesW(
+      PCWSTR::from_raw(monitor_info.szDevice.as_ptr()),
+      0,
+      &mut device,
+      EDD_GET_DEVICE_INTERFACE_NAME,
+    )
+  };
+  (found.as_bool() && device.DeviceID[0] != 0)
+    .then(|| util::wchar_ptr_to_string(PCWSTR::from_raw(device.DeviceID.as_ptr())))
+}
+
+lazy_static! {
+  /// Device names of the displays whose mode was changed to enter exclusive fullscreen and
+  /// hasn't been restored.
+  static ref CHANGED_DISPLAY_MODES: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+}
+
+pub(crate) fn set_display_mode_changed(device_name: &str) {
+  CHANGED_DISPLAY_MODES.lock().insert(device_name.to_owned());
+}
+
+/// Restores the mode of the display named `device_name` from the registry.
+pub(crate) fn restore_display_mode(device_name: &str, flags: CDS_TYPE) -> DISP_CHANGE {
+  CHANGED_DISPLAY_MODES.lock().remove(device_name);
+  let device_name = util::encode_wide(device_name);
+  unsafe {
+    ChangeDisplaySettingsExW(
+      PCWSTR::from_raw(device_name.as_ptr()),
+      None,
+      HWND::default(),
+      flags,
+      None,
+    )
+  }
+}
+
+/// Restores the modes of the displays exclusive fullscreen changed and hasn't restored.
+///
+/// Called when the event loop is destroyed, so the desktop isn't left at the fullscreen
+/// resolution if the app exits or panics without leaving exclusive fullscreen.
+pub(crate) fn restore_display_modes() {
+  let changed = mem::take(&mut *CHANGED_DISPLAY_MODES.lock());
+  for device_name in changed {
+    let res = restore_display_mode(&device_name, CDS_TYPE(0));
+    if res != DISP_CHANGE_SUCCESSFUL {
+      warn!(
+        "Failed to restore the mode of display {}: {:?}",
+        device_name, res
+      );
+    }
+  }
+}
+
 impl MonitorHandle {
   pub(crate) fn new(hmonitor: HMONITOR) -> Self {
-    MonitorHandle(hmonitor.0 as _)
+    MonitorHandle {
+      hmonitor: hmonitor.0 as _,
+      device_path: get_device_path(hmonitor),
+    }
   }
 
   #[inline]
@@ -191,7 +302,7 @@ impl MonitorHandle {
 
   #[inline]
   pub fn hmonitor(&self) -> HMONITOR {
-    HMONITOR(self.0 as _)
+    HMONITOR(self.hmonitor as _)
   }
 
   #[inline]
@@ -219,34 +330,69 @@ impl MonitorHandle {
     dpi_to_scale_factor(get_monitor_dpi(self.hmonitor()).unwrap_or(96))
   }
 
+  pub fn hdr_metadata(&self) -> Option<HdrMetadata> {
+    unsafe {
+      let factory: IDXGIFactory1 = CreateDXGIFactory1().ok()?;
+      let mut adapter_index = 0;
+      while let Ok(adapter) = factory.EnumAdapters1(adapter_index) {
+        let mut output_index = 0;
+        while let Ok(output) = adapter.EnumOutputs(output_index) {
+          // `IDXGIOutput6` is only available on Windows 10 1703 and later
+          if let Ok(desc) = output.cast::<IDXGIOutput6>().and_then(|o| o.GetDesc1()) {
+            if desc.Monitor == self.hmonitor() {
+              return Some(HdrMetadata {
+                supports_hdr: desc.ColorSpace == DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020,
+                max_luminance: Some(desc.MaxLuminance),
+                min_luminance: Some(desc.MinLuminance),
+              });
+            }
+          }
+          output_index += 1;
+        }
+        adapter_index += 1;
+      }
+      None
+    }
+  }
+
   #[inline]
   pub fn video_modes(&self) -> impl Iterator<Item = RootVideoMode> {
     // EnumDisplaySettingsExW can return duplicate values (or some of the
     // fields are probably changing, but we aren't looking at those fields
     // anyway), so we're using a BTreeSet deduplicate
     let mut modes = BTreeSet::new();
-    let mut i = 0;
 
-    loop {
+    let monitor_info = match get_monitor_info(self.hmonitor()) {
+      Ok(monitor_info) => monitor_info,
+      Err(error) => {
+        warn!("Failed to get monitor info to list video modes: {}", error);
+        return modes.into_iter();
+      }
+    };
+    let device_name = PCWSTR::from_raw(monitor_info.szDevice.as_ptr());
+
+    // The current mode is listed first, as the enumeration below may skip it
+    // when it's a mode the driver doesn't advertise, e.g. a custom resolution.
+    let indices =
+      std::iter::once(ENUM_CURRENT_SETTINGS).chain((0..).map(ENUM_DISPLAY_SETTINGS_MODE));
+    for (n, i) in indices.enumerate() {
       unsafe {
-        let monitor_info = get_monitor_info(self.hmonitor()).unwrap();
-        let device_name = PCWSTR::from_raw(monitor_info.szDevice.as_ptr());
         let mut mode: DEVMODEW = mem::zeroed();
         mode.dmSize = mem::size_of_val(&mode) as u16;
-        if !EnumDisplaySettingsExW(
-          device_name,
-          ENUM_DISPLAY_SETTINGS_MODE(i),
-          &mut mode,
-          ENUM_DISPLAY_SETTINGS_FLAGS(0),
-        )
-        .as_bool()
+        if !EnumDisplaySettingsExW(device_name, i, &mut mode, ENUM_DISPLAY_SETTINGS_FLAGS(0))
+          .as_bool()
         {
+          if n == 0 {
+            // Failing to get the current settings doesn't end the enumeration.
+            continue;
+          }
           break;
         }
-        i += 1;
 
         let required_fields = DM_BITSPERPEL | DM_PELSWIDTH | DM_PELSHEIGHT | DM_DISPLAYFREQUENCY;
-        assert!(mode.dmFields & required_fields == required_fields);
+        if mode.dmFields & required_fields != required_fields {
+          continue;
+        }
 
         modes.insert(RootVideoMode {
           video_mode: VideoMode {
@@ -18,12 +18,12 @@ use crate::{
 };
 
 use windows::{
-  core::{HRESULT, PCSTR, PCWSTR},
+  core::{HRESULT, PCSTR, PCWSTR, PWSTR},
   Win32::{
     Foundation::{BOOL, FARPROC, HWND, LPARAM, LRESULT, POINT, RECT, WPARAM},
     Globalization::lstrlenW,
     Graphics::Gdi::{ClientToScreen, InvalidateRgn, HMONITOR, HRGN},
-    System::LibraryLoader::*,
+    System::{LibraryLoader::*, Shutdown::ShutdownBlockReasonQuery},
     UI::{
       HiDpi::*,
       Input::KeyboardAndMouse::*,
@@ -205,16 +205,45 @@ pub fn is_focused(window: HWND) -> bool {
   window == unsafe { GetActiveWindow() }
 }
 
+/// Reads one of the `SPI_GETWHEELSCROLL*` settings. They are read anew on every call so changes,
+/// which are broadcast with `WM_SETTINGCHANGE`, are always picked up.
+fn wheel_scroll_setting(action: SYSTEM_PARAMETERS_INFO_ACTION) -> u32 {
+  // The system default of both settings.
+  let mut value: u32 = 3;
+  let _ = unsafe {
+    SystemParametersInfoW(
+      action,
+      0,
+      Some(&mut value as *mut _ as *mut std::ffi::c_void),
+      SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+    )
+  };
+  value
+}
+
+pub fn wheel_scroll_lines() -> u32 {
+  wheel_scroll_setting(SPI_GETWHEELSCROLLLINES)
+}
+
+pub fn wheel_scroll_chars() -> u32 {
+  wheel_scroll_setting(SPI_GETWHEELSCROLLCHARS)
+}
+
 pub fn is_visible(window: HWND) -> bool {
   unsafe { IsWindowVisible(window).as_bool() }
 }
 
-pub fn is_maximized(window: HWND) -> windows::core::Result<bool> {
+pub fn get_window_placement(window: HWND) -> windows::core::Result<WINDOWPLACEMENT> {
   let mut placement = WINDOWPLACEMENT {
     length: mem::size_of::<WINDOWPLACEMENT>() as u32,
     ..WINDOWPLACEMENT::default()
   };
   unsafe { GetWindowPlacement(window, &mut placement)? };
+  Ok(placement)
+}
+
+pub fn is_maximized(window: HWND) -> windows::core::Result<bool> {
+  let placement = get_window_placement(window)?;
   Ok(placement.showCmd == SW_MAXIMIZE.0 as u32)
 }
 
@@ -232,9 +261,7 @@ impl CursorIcon {
       CursorIcon::Crosshair => IDC_CROSS,
       CursorIcon::Text | CursorIcon::VerticalText => IDC_IBEAM,
       CursorIcon::NotAllowed | CursorIcon::NoDrop => IDC_NO,
-      CursorIcon::Grab | CursorIcon::Grabbing | CursorIcon::Move | CursorIcon::AllScroll => {
-        IDC_SIZEALL
-      }
+      CursorIcon::Move | CursorIcon::AllScroll => IDC_SIZEALL,
       CursorIcon::EResize | CursorIcon::WResize | CursorIcon::EwResize | CursorIcon::ColResize => {
         IDC_SIZEWE
       }
@@ -246,7 +273,8 @@ impl CursorIcon {
       CursorIcon::Wait => IDC_WAIT,
       CursorIcon::Progress => IDC_APPSTARTING,
       CursorIcon::Help => IDC_HELP,
-      _ => IDC_ARROW, // use arrow for the missing cases.
+      // use arrow for the missing cases, see `cursor::load_cursor` for the synthesized ones.
+      _ => IDC_ARROW,
     }
   }
 }
@@ -416,3 +444,67 @@ pub fn get_instance_handle() -> windows::Win32::Foundation::HMODULE {
 
   windows::Win32::Foundation::HMODULE(unsafe { &__ImageBase as *const _ as _ })
 }
+
+/// Returns the windows of the current thread that are directly owned by or parented to `hwnd`.
+pub fn dependent_windows(hwnd: HWND) -> Vec<HWND> {
+  unsafe extern "system" fn collect(window: HWND, lparam: LPARAM) -> BOOL {
+    let (hwnd, dependents) = &mut *(lparam.0 as *mut (HWND, Vec<HWND>));
+    if GetWindow(window, GW_OWNER).ok() == Some(*hwnd) || GetAncestor(window, GA_PARENT) == *hwnd {
+      dependents.push(window);
+    }
+    true.into()
+  }
+
+  let mut data = (hwnd, Vec::new());
+  let lparam = LPARAM(&mut data as *mut _ as _);
+  unsafe {
+    let _ = EnumThreadWindows(
+      windows::Win32::System::Threading::GetCurrentThreadId(),
+      Some(collect),
+      lparam,
+    );
+    let _ = EnumChildWindows(hwnd, Some(collect), lparam);
+  }
+  data.1
+}
+
+/// Returns whether one of the windows of the current thread has a shutdown block reason set.
+pub fn has_shutdown_block_reason() -> bool {
+  unsafe extern "system" fn check(window: HWND, lparam: LPARAM) -> BOOL {
+    let mut len = 0;
+    if ShutdownBlockReasonQuery(window, PWSTR::null(), &mut len).is_ok() {
+      *(lparam.0 as *mut bool) = true;
+      return false.into();
+    }
+    true.into()
+  }
+
+  let mut blocked = false;
+  let lparam = LPARAM(&mut blocked as *mut _ as _);
+  unsafe {
+    let _ = EnumThreadWindows(
+      windows::Win32::System::Threading::GetCurrentThreadId(),
+      Some(check),
+      lparam,
+    );
+  }
+  blocked
+}
+
+/// Returns the top-level windows owned by `hwnd`.
+pub fn owned_windows(hwnd: HWND) -> Vec<HWND> {
+  unsafe extern "system" fn collect(window: HWND, lparam: LPARAM) -> BOOL {
+    let (hwnd, owned) = &mut *(lparam.0 as *mut (HWND, Vec<HWND>));
+    if GetWindow(window, GW_OWNER).ok() == Some(*hwnd) {
+      owned.push(window);
+    }
+    true.into()
+  }
+
+  let mut data = (hwnd, Vec::new());
+  let lparam = LPARAM(&mut data as *mut _ as _);
+  unsafe {
+    let _ = EnumWindows(Some(collect), lparam);
+  }
+  data.1
+}
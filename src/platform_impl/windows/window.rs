@@ -25,7 +25,12 @@ use windows::{
       Dwm::{DwmEnableBlurBehindWindow, DWM_BB_BLURREGION, DWM_BB_ENABLE, DWM_BLURBEHIND},
       Gdi::*,
     },
-    System::{Com::*, LibraryLoader::*, Ole::*},
+    System::{
+      Com::*,
+      LibraryLoader::*,
+      Ole::*,
+      Shutdown::{ShutdownBlockReasonCreate, ShutdownBlockReasonDestroy},
+    },
     UI::{
       Input::{Ime::*, KeyboardAndMouse::*, Touch::*},
       Shell::{ITaskbarList4 as ITaskbarList, TaskbarList, *},
@@ -35,14 +40,18 @@ use windows::{
 };
 
 use crate::{
-  dpi::{PhysicalPosition, PhysicalSize, Position, Size},
+  dpi::{self, PhysicalPosition, PhysicalSize, Position, Size},
   error::{ExternalError, NotSupportedError, OsError as RootOsError},
+  event::Event,
   icon::Icon,
   monitor::MonitorHandle as RootMonitorHandle,
+  platform::windows::CornerPreference,
   platform_impl::platform::{
+    blur, cursor,
     dark_mode::try_window_theme,
     dpi::{dpi_to_scale_factor, hwnd_dpi},
     drop_handler::FileDropHandler,
+    dwm,
     event_loop::{self, EventLoopWindowTarget, DESTROY_MSG_ID},
     icon::{self, IconType},
     monitor, util,
@@ -50,13 +59,14 @@ use crate::{
     OsError, Parent, PlatformSpecificWindowBuilderAttributes, WindowId,
   },
   window::{
-    CursorIcon, Fullscreen, ProgressBarState, ProgressState, ResizeDirection, Theme,
-    UserAttentionType, WindowAttributes, WindowSizeConstraints,
+    clamp_frame_to_work_areas, CursorIcon, Fullscreen, HitTestHandler, ProgressBarState,
+    ProgressState, ResizeDirection, Theme, UserAttentionType, WindowAttributes,
+    WindowSizeConstraints, RGBA,
   },
 };
 
 use super::{
-  event_loop::CHANGE_THEME_MSG_ID,
+  event_loop::{CHANGE_THEME_MSG_ID, SET_DRAG_AND_DROP_MSG_ID},
   keyboard::{KeyEventBuilder, KEY_EVENT_BUILDERS},
 };
 
@@ -97,35 +107,15 @@ impl Window {
       let drag_and_drop = pl_attr.drag_and_drop;
       init(w_attr, pl_attr, event_loop).map(|win| {
         let file_drop_handler = if drag_and_drop {
-          // It is ok if the initialize result is `S_FALSE` because it might happen that
-          // multiple windows are created on the same thread.
-          if let Err(error) = OleInitialize(None) {
-            match error.code() {
-              win32f::OLE_E_WRONGCOMPOBJ => {
-                panic!("OleInitialize failed! Result was: `OLE_E_WRONGCOMPOBJ`")
-              }
-              win32f::RPC_E_CHANGED_MODE => panic!(
-                "OleInitialize failed! Result was: `RPC_E_CHANGED_MODE`. \
-                Make sure other crates are not using multithreaded COM library \
-                on the same thread or disable drag and drop support."
-              ),
-              _ => (),
-            };
-          }
-
           let file_drop_runner = event_loop.runner_shared.clone();
-          let file_drop_handler: IDropTarget = FileDropHandler::new(
+          Some(register_drop_target(
             win.window.0,
             Box::new(move |event| {
               if let Ok(e) = event.map_nonuser_event() {
                 file_drop_runner.send_event(e)
               }
             }),
-          )
-          .into();
-
-          assert!(RegisterDragDrop(win.window.0, &file_drop_handler).is_ok());
-          Some(file_drop_handler)
+          ))
         } else {
           None
         };
@@ -133,7 +123,7 @@ impl Window {
         let subclass_input = event_loop::SubclassInput {
           window_state: win.window_state.clone(),
           event_loop_runner: event_loop.runner_shared.clone(),
-          _file_drop_handler: file_drop_handler,
+          file_drop_handler: RefCell::new(file_drop_handler),
           subclass_removed: Cell::new(false),
           recurse_depth: Cell::new(0),
           event_loop_preferred_theme: event_loop.preferred_theme.clone(),
@@ -155,17 +145,49 @@ impl Window {
   pub fn title(&self) -> String {
     let len = unsafe { GetWindowTextLengthW(self.window.0) };
     let mut buf = vec![0; (len + 1) as usize];
-    unsafe { GetWindowTextW(self.window.0, &mut buf) };
-    String::from_utf16_lossy(&buf[..len as _])
+    let copied = unsafe { GetWindowTextW(self.window.0, &mut buf) };
+    decode_title(&buf, copied, len)
   }
   #[inline]
   pub fn set_visible(&self, visible: bool) {
     let window = self.window.0 .0 as isize;
     let window_state = Arc::clone(&self.window_state);
     self.thread_executor.execute_in_thread(move || {
-      WindowState::set_window_flags(window_state.lock(), HWND(window as _), |f| {
+      let window_state = window_state.lock();
+      let skip_taskbar = window_state.skip_taskbar;
+      let always_on_top = window_state
+        .window_flags()
+        .contains(WindowFlags::ALWAYS_ON_TOP);
+      WindowState::set_window_flags(window_state, HWND(window as _), |f| {
         f.set(WindowFlags::VISIBLE, visible)
       });
+
+      // showing a window adds its taskbar button back, so drop it again
+      if visible && skip_taskbar {
+        let _ = unsafe { set_skip_taskbar(HWND(window as _), true) };
+      }
+
+      // topmost set while the window was hidden isn't always kept once it is shown, assert it
+      if visible && always_on_top {
+        unsafe {
+          let _ = SetWindowPos(
+            HWND(window as _),
+            HWND_TOPMOST,
+            0,
+            0,
+            0,
+            0,
+            SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+          );
+        }
+      }
+
+      // make sure a freshly shown window gets its first `RedrawRequested`
+      if visible {
+        unsafe {
+          let _ = RedrawWindow(HWND(window as _), None, HRGN::default(), RDW_INTERNALPAINT);
+        }
+      }
     });
   }
 
@@ -183,12 +205,42 @@ impl Window {
     }
   }
 
+  #[inline]
+  pub fn activate(&self) {
+    self.set_visible(true);
+    self.set_minimized(false);
+
+    // queued after the requests above, so the window is already shown and restored
+    let window = self.window.0 .0 as isize;
+    self.thread_executor.execute_in_thread(move || unsafe {
+      let window = HWND(window as _);
+      if window != GetForegroundWindow() {
+        force_window_active(window);
+      }
+    });
+  }
+
   #[inline]
   pub fn is_focused(&self) -> bool {
     let window_state = self.window_state.lock();
     window_state.has_active_focus()
   }
 
+  #[inline]
+  pub fn owner(&self) -> Option<WindowId> {
+    unsafe { GetWindow(self.hwnd(), GW_OWNER) }
+      .ok()
+      .map(|owner| WindowId(owner.0 as _))
+  }
+
+  #[inline]
+  pub fn owned_windows(&self) -> Vec<WindowId> {
+    util::owned_windows(self.hwnd())
+      .into_iter()
+      .map(|window| WindowId(window.0 as _))
+      .collect()
+  }
+
   #[inline]
   pub fn request_redraw(&self) {
     unsafe {
@@ -219,7 +271,10 @@ impl Window {
     let window_state = Arc::clone(&self.window_state);
     let window = self.window.0 .0 as isize;
     self.thread_executor.execute_in_thread(move || {
-      WindowState::set_window_flags(window_state.lock(), HWND(window as _), |f| {
+      let mut window_state = window_state.lock();
+      // The new position takes precedence over the geometry saved by `set_maximized`.
+      window_state.saved_maximized_window = None;
+      WindowState::set_window_flags(window_state, HWND(window as _), |f| {
         f.set(WindowFlags::MAXIMIZED, false)
       });
     });
@@ -276,7 +331,10 @@ impl Window {
 
     let window = self.window.0 .0 as isize;
     self.thread_executor.execute_in_thread(move || {
-      WindowState::set_window_flags(window_state.lock(), HWND(window as _), |f| {
+      let mut window_state = window_state.lock();
+      // The new size takes precedence over the geometry saved by `set_maximized`.
+      window_state.saved_maximized_window = None;
+      WindowState::set_window_flags(window_state, HWND(window as _), |f| {
         f.set(WindowFlags::MAXIMIZED, false)
       });
     });
@@ -430,10 +488,7 @@ impl Window {
   #[inline]
   pub fn set_cursor_icon(&self, cursor: CursorIcon) {
     self.window_state.lock().mouse.cursor = cursor;
-    self.thread_executor.execute_in_thread(move || unsafe {
-      let cursor = LoadCursorW(HMODULE::default(), cursor.to_windows_cursor()).unwrap_or_default();
-      SetCursor(cursor);
-    });
+    self.thread_executor.execute_in_thread(cursor::refresh);
   }
 
   #[inline]
@@ -497,6 +552,14 @@ impl Window {
     }
   }
 
+  #[inline]
+  pub fn set_cursor_position_screen(&self, position: Position) -> Result<(), ExternalError> {
+    let (x, y) = position.to_physical::<i32>(self.scale_factor()).into();
+    unsafe {
+      SetCursorPos(x, y).map_err(|e| ExternalError::Os(os_error!(OsError::IoError(e.into()))))
+    }
+  }
+
   fn handle_os_dragging(&self, wparam: WPARAM) -> Result<(), ExternalError> {
     let points = {
       let mut pos = unsafe { mem::zeroed() };
@@ -574,7 +637,34 @@ impl Window {
     let window_state = Arc::clone(&self.window_state);
 
     self.thread_executor.execute_in_thread(move || {
-      WindowState::set_window_flags(window_state.lock(), HWND(window as _), |f| {
+      let hwnd = HWND(window as _);
+      let mut window_state_lock = window_state.lock();
+      let is_maximized = window_state_lock
+        .window_flags
+        .contains(WindowFlags::MAXIMIZED);
+
+      if maximized && !is_maximized && window_state_lock.fullscreen.is_none() {
+        // Save window bounds before maximizing, `SW_RESTORE` relies on the normal position
+        // stored by the OS which may have been changed in the meantime.
+        window_state_lock.saved_maximized_window = util::get_window_placement(hwnd)
+          .ok()
+          .map(|placement| SavedWindow { placement });
+      } else if !maximized && is_maximized {
+        if let Some(SavedWindow { placement: saved }) =
+          window_state_lock.saved_maximized_window.take()
+        {
+          // Update the normal position while still maximized so the following
+          // `SW_RESTORE` goes straight to the saved bounds.
+          if let Ok(mut placement) = util::get_window_placement(hwnd) {
+            placement.rcNormalPosition = saved.rcNormalPosition;
+            unsafe {
+              let _ = SetWindowPlacement(hwnd, &placement);
+            }
+          }
+        }
+      }
+
+      WindowState::set_window_flags(window_state_lock, hwnd, |f| {
         f.set(WindowFlags::MAXIMIZED, maximized)
       });
     });
@@ -655,10 +745,10 @@ impl Window {
       _ if old_fullscreen == fullscreen => return,
       // Return if saved Borderless(monitor) is the same as current monitor when requested fullscreen is Borderless(None)
       (Some(Fullscreen::Borderless(Some(monitor))), Some(Fullscreen::Borderless(None)))
-        if monitor.inner == monitor::current_monitor(window.0) =>
-      {
-        return
-      }
+      | (
+        Some(Fullscreen::BorderlessWorkArea(Some(monitor))),
+        Some(Fullscreen::BorderlessWorkArea(None)),
+      ) if monitor.inner == monitor::current_monitor(window.0) => return,
       _ => {}
     }
 
@@ -670,15 +760,31 @@ impl Window {
       let hwnd = HWND(window_isize as _);
       // Change video mode if we're transitioning to or from exclusive
       // fullscreen
-      match (&old_fullscreen, &fullscreen) {
+      let display_mode_changed = match (&old_fullscreen, &fullscreen) {
         (&None, &Some(Fullscreen::Exclusive(ref video_mode)))
-        | (&Some(Fullscreen::Borderless(_)), &Some(Fullscreen::Exclusive(ref video_mode)))
+        | (
+          &Some(Fullscreen::Borderless(_) | Fullscreen::BorderlessWorkArea(_)),
+          &Some(Fullscreen::Exclusive(ref video_mode)),
+        )
         | (&Some(Fullscreen::Exclusive(_)), &Some(Fullscreen::Exclusive(ref video_mode))) => {
           let monitor = video_mode.monitor();
+          let device_name = monitor.inner.native_identifier();
+
+          // Leave the display we're moving away from at its own mode
+          if let Some(Fullscreen::Exclusive(ref old_mode)) = old_fullscreen {
+            let old_device_name = old_mode.monitor().inner.native_identifier();
+            if old_device_name != device_name {
+              let res = monitor::restore_display_mode(&old_device_name, CDS_FULLSCREEN);
+              if res != DISP_CHANGE_SUCCESSFUL {
+                warn!(
+                  "Failed to restore the mode of display {}: {:?}",
+                  old_device_name, res
+                );
+              }
+            }
+          }
 
-          let mut display_name = OsStr::new(&monitor.inner.native_identifier())
-            .encode_wide()
-            .collect::<Vec<_>>();
+          let mut display_name = OsStr::new(&device_name).encode_wide().collect::<Vec<_>>();
           // `encode_wide` does not add a null-terminator but
           // `ChangeDisplaySettingsExW` requires a null-terminated
           // string, so add it
@@ -701,23 +807,30 @@ impl Window {
           debug_assert!(res != DISP_CHANGE_BADPARAM);
           debug_assert!(res != DISP_CHANGE_FAILED);
           assert_eq!(res, DISP_CHANGE_SUCCESSFUL);
+          monitor::set_display_mode_changed(&device_name);
+          true
         }
-        (&Some(Fullscreen::Exclusive(_)), &None)
-        | (&Some(Fullscreen::Exclusive(_)), &Some(Fullscreen::Borderless(_))) => {
-          let res = unsafe {
-            ChangeDisplaySettingsExW(PCWSTR::null(), None, HWND::default(), CDS_FULLSCREEN, None)
-          };
+        (&Some(Fullscreen::Exclusive(ref video_mode)), &None)
+        | (
+          &Some(Fullscreen::Exclusive(ref video_mode)),
+          &Some(Fullscreen::Borderless(_) | Fullscreen::BorderlessWorkArea(_)),
+        ) => {
+          let res = monitor::restore_display_mode(
+            &video_mode.monitor().inner.native_identifier(),
+            CDS_FULLSCREEN,
+          );
 
           debug_assert!(res != DISP_CHANGE_BADFLAGS);
           debug_assert!(res != DISP_CHANGE_BADMODE);
           debug_assert!(res != DISP_CHANGE_BADPARAM);
           debug_assert!(res != DISP_CHANGE_FAILED);
           assert_eq!(res, DISP_CHANGE_SUCCESSFUL);
+          true
         }
-        _ => (),
-      }
+        _ => false,
+      };
 
-      unsafe {
+      if display_mode_changed {
         // There are some scenarios where calling `ChangeDisplaySettingsExW` takes long
         // enough to execute that the DWM thinks our program has frozen and takes over
         // our program's window. When that happens, the `SetWindowPos` call below gets
@@ -725,9 +838,9 @@ impl Window {
         //
         // Calling `PeekMessageW` here notifies Windows that our process is still running
         // fine, taking control back from the DWM and ensuring that the `SetWindowPos` call
-        // below goes through.
+        // below goes through. Borderless fullscreen changes don't need this.
         let mut msg = MSG::default();
-        let _ = PeekMessageW(&mut msg, HWND::default(), 0, 0, PM_NOREMOVE);
+        let _ = unsafe { PeekMessageW(&mut msg, HWND::default(), 0, 0, PM_NOREMOVE) };
       }
 
       // Update window style
@@ -738,7 +851,10 @@ impl Window {
         );
         f.set(
           WindowFlags::MARKER_BORDERLESS_FULLSCREEN,
-          matches!(fullscreen, Some(Fullscreen::Borderless(_))),
+          matches!(
+            fullscreen,
+            Some(Fullscreen::Borderless(_) | Fullscreen::BorderlessWorkArea(_))
+          ),
         );
       });
 
@@ -746,24 +862,39 @@ impl Window {
       match &fullscreen {
         Some(fullscreen) => {
           // Save window bounds before entering fullscreen
-          let placement = unsafe {
-            let mut placement = WINDOWPLACEMENT::default();
-            let _ = GetWindowPlacement(hwnd, &mut placement);
-            placement
-          };
-
-          window_state.lock().saved_window = Some(SavedWindow { placement });
+          if let Ok(placement) = util::get_window_placement(hwnd) {
+            window_state.lock().saved_window = Some(SavedWindow { placement });
+          }
 
           let monitor = match &fullscreen {
             Fullscreen::Exclusive(video_mode) => video_mode.monitor(),
-            Fullscreen::Borderless(Some(monitor)) => monitor.clone(),
-            Fullscreen::Borderless(None) => RootMonitorHandle {
-              inner: monitor::current_monitor(hwnd),
-            },
+            Fullscreen::Borderless(Some(monitor))
+            | Fullscreen::BorderlessWorkArea(Some(monitor)) => monitor.clone(),
+            Fullscreen::Borderless(None) | Fullscreen::BorderlessWorkArea(None) => {
+              RootMonitorHandle {
+                inner: monitor::current_monitor(hwnd),
+              }
+            }
           };
 
-          let position: (i32, i32) = monitor.position().into();
-          let size: (u32, u32) = monitor.size().into();
+          let (position, size): ((i32, i32), (u32, u32)) = match &fullscreen {
+            Fullscreen::BorderlessWorkArea(_) => {
+              match monitor::get_monitor_info(monitor.inner.hmonitor()) {
+                Ok(info) => {
+                  let work = info.monitorInfo.rcWork;
+                  (
+                    (work.left, work.top),
+                    (
+                      (work.right - work.left) as u32,
+                      (work.bottom - work.top) as u32,
+                    ),
+                  )
+                }
+                Err(_) => (monitor.position().into(), monitor.size().into()),
+              }
+            }
+            _ => (monitor.position().into(), monitor.size().into()),
+          };
 
           unsafe {
             let _ = SetWindowPos(
@@ -790,8 +921,12 @@ impl Window {
         }
       }
 
+      // The taskbar stays visible when only the work area is covered
       unsafe {
-        taskbar_mark_fullscreen(hwnd, fullscreen.is_some());
+        taskbar_mark_fullscreen(
+          hwnd,
+          fullscreen.is_some() && !matches!(fullscreen, Some(Fullscreen::BorderlessWorkArea(_))),
+        );
       }
     });
   }
@@ -802,7 +937,14 @@ impl Window {
     let window_state = Arc::clone(&self.window_state);
 
     self.thread_executor.execute_in_thread(move || {
-      WindowState::set_window_flags(window_state.lock(), HWND(window as _), |f| {
+      let window_state = window_state.lock();
+      // undecorated windows have no frame to draw the menu bar in, detach it until the
+      // decorations come back
+      if let Some(menu) = window_state.menu {
+        let menu = if decorations { menu } else { HMENU::default() };
+        let _ = unsafe { SetMenu(HWND(window as _), menu) };
+      }
+      WindowState::set_window_flags(window_state, HWND(window as _), |f| {
         f.set(WindowFlags::MARKER_DECORATIONS, decorations)
       });
     });
@@ -862,6 +1004,11 @@ impl Window {
     self.window_state.lock().window_icon = window_icon;
   }
 
+  #[inline]
+  pub fn window_icon(&self) -> Option<Icon> {
+    self.window_state.lock().window_icon.clone()
+  }
+
   #[inline]
   pub fn set_taskbar_icon(&self, taskbar_icon: Option<Icon>) {
     if let Some(ref taskbar_icon) = taskbar_icon {
@@ -895,6 +1042,17 @@ impl Window {
     self.set_ime_position_physical(x, y);
   }
 
+  #[inline]
+  pub fn set_ime_allowed(&self, allowed: bool) {
+    self.window_state.lock().ime_allowed = allowed;
+    let window = self.window.0 .0 as isize;
+    self.thread_executor.execute_in_thread(move || unsafe {
+      // associating the default context enables the IME, associating none disables it
+      let flags = if allowed { IACE_DEFAULT } else { 0 };
+      let _ = ImmAssociateContextEx(HWND(window as _), HIMC::default(), flags);
+    });
+  }
+
   #[inline]
   pub fn request_user_attention(&self, request_type: Option<UserAttentionType>) {
     let window = self.window.clone();
@@ -976,6 +1134,39 @@ impl Window {
     }
   }
 
+  #[inline]
+  pub fn begin_system_move(&self) {
+    self.begin_system_command(SC_MOVE)
+  }
+
+  #[inline]
+  pub fn begin_system_resize(&self) {
+    self.begin_system_command(SC_SIZE)
+  }
+
+  /// Runs a `WM_SYSCOMMAND` command as if it was picked from the window menu. The default window
+  /// procedure handles `SC_MOVE` and `SC_SIZE` for undecorated windows too.
+  fn begin_system_command(&self, command: u32) {
+    unsafe {
+      let _ = ReleaseCapture();
+      let _ = PostMessageW(self.hwnd(), WM_SYSCOMMAND, WPARAM(command as _), LPARAM(0));
+    }
+  }
+
+  #[inline]
+  pub fn set_enable_drag_drop(&self, enabled: bool) {
+    // The drop target is owned by the subclass input, so the window procedure registers or
+    // revokes it on the window's thread.
+    unsafe {
+      SendMessageW(
+        self.hwnd(),
+        *SET_DRAG_AND_DROP_MSG_ID,
+        WPARAM(enabled as _),
+        LPARAM(0),
+      )
+    };
+  }
+
   #[inline]
   pub(crate) fn set_skip_taskbar(&self, skip: bool) -> Result<(), ExternalError> {
     self.window_state.lock().skip_taskbar = skip;
@@ -985,7 +1176,12 @@ impl Window {
   #[inline]
   pub fn set_progress_bar(&self, progress: ProgressBarState) {
     unsafe {
-      let taskbar_list: ITaskbarList = CoCreateInstance(&TaskbarList, None, CLSCTX_SERVER).unwrap();
+      com_initialized();
+      let taskbar_list: ITaskbarList = match CoCreateInstance(&TaskbarList, None, CLSCTX_SERVER) {
+        Ok(taskbar_list) => taskbar_list,
+        // There is no taskbar to report progress to, e.g. when explorer isn't running.
+        Err(_) => return,
+      };
       let handle = self.window.0;
 
       if let Some(state) = progress.state {
@@ -1026,16 +1222,102 @@ impl Window {
     });
   }
 
+  pub fn set_blur(&self, blur: bool) {
+    if !blur::set_blur(self.hwnd(), blur) {
+      warn!("Blur behind the window is not supported on this version of Windows");
+    }
+  }
+
+  #[inline]
+  pub fn set_drag_region(&self, region: Option<(Position, Size)>) {
+    self.window_state.lock().drag_region = region;
+  }
+
+  #[inline]
+  pub fn set_resize_border_thickness(&self, thickness: Option<f64>) {
+    self.window_state.lock().resize_border_thickness = thickness;
+  }
+
+  #[inline]
+  pub fn set_hit_test_handler(&self, handler: Option<HitTestHandler>) {
+    self.window_state.lock().hit_test_handler = handler.map(Arc::from);
+  }
+
+  #[inline]
+  pub fn set_corner_preference(&self, preference: CornerPreference) {
+    if !dwm::set_corner_preference(self.hwnd(), preference) {
+      warn!("Setting the corner preference is only supported on Windows 11");
+    }
+  }
+
+  #[inline]
+  pub fn set_frame_color(&self, frame: dwm::FrameColor, color: Option<RGBA>) {
+    if !dwm::set_frame_color(self.hwnd(), frame, color) {
+      warn!("Setting the frame colors is only supported on Windows 11");
+    }
+  }
+
+  #[inline]
+  pub fn set_intercept_alt_f4(&self, intercept: bool) {
+    self.window_state.lock().intercept_alt_f4 = intercept;
+  }
+
+  pub fn force_scale_factor(&self, scale_factor: Option<f64>) {
+    if let Some(scale_factor) = scale_factor {
+      if !dpi::validate_scale_factor(scale_factor) {
+        warn!("Ignoring invalid forced scale factor {}", scale_factor);
+        return;
+      }
+    }
+
+    self.window_state.lock().forced_scale_factor = scale_factor;
+
+    // go through the regular `WM_DPICHANGED` path so the window is resized and
+    // `ScaleFactorChanged` is emitted exactly like on a real DPI change
+    let window = self.window.0 .0 as isize;
+    self.thread_executor.execute_in_thread(move || unsafe {
+      let hwnd = HWND(window as _);
+      let dpi = hwnd_dpi(hwnd) as usize;
+      if let Some(rect) = util::get_window_rect(hwnd) {
+        SendMessageW(
+          hwnd,
+          WM_DPICHANGED,
+          WPARAM(dpi | (dpi << 16)),
+          LPARAM(&rect as *const RECT as _),
+        );
+      }
+    });
+  }
+
+  pub fn set_shutdown_block_reason(&self, reason: Option<&str>) {
+    // the reason must be set from the thread that created the window
+    let window = self.window.0 .0 as isize;
+    let reason = reason.map(util::encode_wide);
+    self.thread_executor.execute_in_thread(move || unsafe {
+      let hwnd = HWND(window as _);
+      let result = match &reason {
+        Some(reason) => ShutdownBlockReasonCreate(hwnd, PCWSTR::from_raw(reason.as_ptr())),
+        None => ShutdownBlockReasonDestroy(hwnd),
+      };
+      if let Err(e) = result {
+        warn!("Failed to set the shutdown block reason: {}", e);
+      }
+    });
+  }
+
   pub fn set_content_protection(&self, enabled: bool) {
-    unsafe {
-      let _ = SetWindowDisplayAffinity(
-        self.hwnd(),
-        if enabled {
-          WDA_EXCLUDEFROMCAPTURE
-        } else {
-          WDA_NONE
-        },
-      );
+    unsafe { set_content_protection(self.hwnd(), enabled) };
+  }
+
+  pub fn is_content_protected(&self) -> bool {
+    unsafe { has_content_protection(self.hwnd()) }
+  }
+
+  pub fn set_content_protection_with_owned_windows(&self, enabled: bool) {
+    let mut windows = vec![self.hwnd()];
+    while let Some(window) = windows.pop() {
+      unsafe { set_content_protection(window, enabled) };
+      windows.extend(util::owned_windows(window));
     }
   }
 }
@@ -1169,14 +1451,24 @@ unsafe fn init<T: 'static>(
     false,
   );
 
+  // for child windows, the menu handle is the child window id instead
+  let menu = pl_attribs
+    .menu
+    .filter(|_| !window_flags.contains(WindowFlags::CHILD));
+  // the menu bar is attached again when the decorations are enabled
+  if menu.is_some() && !attributes.decorations {
+    let _ = SetMenu(real_window.0, HMENU::default());
+  }
+
   let window_state = {
-    let window_state = WindowState::new(
+    let mut window_state = WindowState::new(
       &attributes,
       None,
       scale_factor,
       current_theme,
       attributes.preferred_theme,
     );
+    window_state.menu = menu;
     let window_state = Arc::new(Mutex::new(window_state));
     WindowState::set_window_flags(window_state.lock(), real_window.0, |f| *f = window_flags);
     window_state
@@ -1196,7 +1488,8 @@ unsafe fn init<T: 'static>(
   win.set_window_icon(attributes.window_icon);
   win.set_taskbar_icon(pl_attribs.taskbar_icon);
 
-  if attributes.fullscreen.is_some() {
+  let fullscreen = attributes.fullscreen.is_some();
+  if fullscreen {
     win.set_fullscreen(attributes.fullscreen);
     force_window_active(win.window.0);
   } else {
@@ -1215,20 +1508,79 @@ unsafe fn init<T: 'static>(
     }
   }
 
-  if attributes.content_protection {
+  // owned windows inherit the content protection of their owner
+  let owner_protected = GetWindow(win.hwnd(), GW_OWNER)
+    .ok()
+    .is_some_and(|owner| has_content_protection(owner));
+  if attributes.content_protection || owner_protected {
     win.set_content_protection(true);
   }
 
   win.set_visible(attributes.visible);
   win.set_closable(attributes.closable);
 
-  if let Some(position) = attributes.position {
+  // Showing the window doesn't give it the focus, and with it the initial `Focused(true)`,
+  // when another application is in the foreground. Ask for it, but let the system decide.
+  if attributes.visible && attributes.focused && !fullscreen {
+    let _ = SetForegroundWindow(win.window.0);
+  }
+
+  if let Some(below) = attributes.below.filter(|_| attributes.visible) {
+    let _ = SetWindowPos(
+      win.hwnd(),
+      HWND(below.0 .0 as _),
+      0,
+      0,
+      0,
+      0,
+      SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+    );
+  }
+
+  if let Some(mut position) = attributes.position {
+    if attributes.position_clamped {
+      position = clamp_position_to_work_areas(&win, position);
+    }
     win.set_outer_position(position);
   }
 
   Ok(win)
 }
 
+/// Moves `position` onto the work area of the nearest monitor when the top of the window placed
+/// there wouldn't be on any monitor's work area.
+fn clamp_position_to_work_areas(win: &Window, position: Position) -> Position {
+  let rect = match unsafe { util::get_window_rect(win.hwnd()) } {
+    Some(rect) => rect,
+    None => return position,
+  };
+  let scale_factor = win.scale_factor();
+  let PhysicalPosition { x, y } = position.to_physical::<f64>(scale_factor);
+  let frame = (
+    x,
+    y,
+    x + (rect.right - rect.left) as f64,
+    y + (rect.bottom - rect.top) as f64,
+  );
+  let work_areas: Vec<_> = monitor::available_monitors()
+    .iter()
+    .filter_map(|monitor| monitor::get_monitor_info(monitor.hmonitor()).ok())
+    .map(|info| {
+      let work = info.monitorInfo.rcWork;
+      (
+        work.left as f64,
+        work.top as f64,
+        work.right as f64,
+        work.bottom as f64,
+      )
+    })
+    .collect();
+  match clamp_frame_to_work_areas(frame, &work_areas, scale_factor) {
+    Some((x, y)) => PhysicalPosition::new(x.round() as i32, y.round() as i32).into(),
+    None => position,
+  }
+}
+
 unsafe fn register_window_class(window_classname: &str) -> Vec<u16> {
   let class_name = util::encode_wide(window_classname);
 
@@ -1398,6 +1750,22 @@ unsafe fn force_window_active(handle: HWND) {
   let _ = SetForegroundWindow(handle);
 }
 
+unsafe fn set_content_protection(hwnd: HWND, enabled: bool) {
+  let _ = SetWindowDisplayAffinity(
+    hwnd,
+    if enabled {
+      WDA_EXCLUDEFROMCAPTURE
+    } else {
+      WDA_NONE
+    },
+  );
+}
+
+unsafe fn has_content_protection(hwnd: HWND) -> bool {
+  let mut affinity = 0;
+  GetWindowDisplayAffinity(hwnd, &mut affinity).is_ok() && affinity != WDA_NONE.0
+}
+
 pub(crate) unsafe fn set_skip_taskbar(hwnd: HWND, skip: bool) -> Result<(), ExternalError> {
   com_initialized();
   let taskbar_list: ITaskbarList = CoCreateInstance(&TaskbarList, None, CLSCTX_SERVER)?;
@@ -1424,3 +1792,64 @@ impl ResizeDirection {
     }
   }
 }
+
+/// Registers a `FileDropHandler` as the drop target of the window, initializing OLE on the
+/// current thread first.
+pub(crate) unsafe fn register_drop_target(
+  hwnd: HWND,
+  send_event: Box<dyn Fn(Event<'static, ()>)>,
+) -> IDropTarget {
+  // It is ok if the initialize result is `S_FALSE` because it might happen that
+  // multiple windows are created on the same thread.
+  if let Err(error) = OleInitialize(None) {
+    match error.code() {
+      win32f::OLE_E_WRONGCOMPOBJ => {
+        panic!("OleInitialize failed! Result was: `OLE_E_WRONGCOMPOBJ`")
+      }
+      win32f::RPC_E_CHANGED_MODE => panic!(
+        "OleInitialize failed! Result was: `RPC_E_CHANGED_MODE`. \
+        Make sure other crates are not using multithreaded COM library \
+        on the same thread or disable drag and drop support."
+      ),
+      _ => (),
+    };
+  }
+
+  let file_drop_handler: IDropTarget = FileDropHandler::new(hwnd, send_event).into();
+  assert!(RegisterDragDrop(hwnd, &file_drop_handler).is_ok());
+  file_drop_handler
+}
+
+/// Decodes the first `copied` code units of a title read with `GetWindowTextW`.
+///
+/// `GetWindowTextLengthW` may return a larger value than the actual length of the title, so only
+/// the code units that were actually copied are decoded to avoid trailing garbage.
+fn decode_title(buf: &[u16], copied: i32, len: i32) -> String {
+  String::from_utf16_lossy(&buf[..copied.clamp(0, len) as usize])
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn title_keeps_surrogate_pairs() {
+    // non-BMP characters are encoded as surrogate pairs in UTF-16
+    for title in ["tao window", "👩‍👩‍👧‍👦 family", "窗口标题 🦀", "𝕥𝕒𝕠"]
+    {
+      let mut buf: Vec<u16> = title.encode_utf16().collect();
+      let len = buf.len() as i32;
+      buf.push(0);
+      assert_eq!(decode_title(&buf, len, len), title);
+    }
+  }
+
+  #[test]
+  fn title_ignores_units_that_were_not_copied() {
+    let mut buf: Vec<u16> = "tao".encode_utf16().collect();
+    // the length was overestimated, the rest of the buffer is garbage
+    buf.extend([0, 0xD83E, 0x41]);
+    assert_eq!(decode_title(&buf, 3, 5), "tao");
+    assert_eq!(decode_title(&buf, -1, 5), "");
+  }
+}
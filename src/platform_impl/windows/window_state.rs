@@ -3,14 +3,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-  dpi::PhysicalPosition,
+  dpi::{PhysicalPosition, Position, Size},
   icon::Icon,
   keyboard::ModifiersState,
   platform_impl::platform::{event_loop, minimal_ime::MinimalIme, util},
-  window::{CursorIcon, Fullscreen, Theme, WindowAttributes, WindowSizeConstraints},
+  window::{
+    CursorIcon, Fullscreen, HitTestResult, Theme, WindowAttributes, WindowMode,
+    WindowSizeConstraints,
+  },
 };
 use parking_lot::MutexGuard;
-use std::io;
+use std::{io, sync::Arc};
 use windows::Win32::{
   Foundation::{HWND, LPARAM, RECT, WPARAM},
   Graphics::Gdi::{InvalidateRgn, HRGN},
@@ -27,19 +30,50 @@ pub struct WindowState {
   pub window_icon: Option<Icon>,
   pub taskbar_icon: Option<Icon>,
 
+  /// The menu bar set with `WindowBuilderExtWindows::with_menu`, detached from the window while
+  /// it is undecorated.
+  pub menu: Option<HMENU>,
+
   pub saved_window: Option<SavedWindow>,
+  /// The window placement before it was maximized through `Window::set_maximized`,
+  /// used to restore the exact same geometry when it gets unmaximized.
+  pub saved_maximized_window: Option<SavedWindow>,
   pub scale_factor: f64,
 
   pub dragging: bool,
 
   pub skip_taskbar: bool,
 
+  /// Whether Alt+F4 is delivered as keyboard input instead of closing the window.
+  pub intercept_alt_f4: bool,
+
+  /// Region of the client area that acts as a title bar.
+  pub drag_region: Option<(Position, Size)>,
+
+  /// Thickness of the resize edges of undecorated windows, in logical pixels.
+  pub resize_border_thickness: Option<f64>,
+
+  /// Set with `Window::set_hit_test_handler`, used by `WM_NCHITTEST`. Shared so it can be called
+  /// without holding the lock.
+  pub hit_test_handler: Option<Arc<dyn Fn(PhysicalPosition<f64>) -> HitTestResult + Send>>,
+
+  /// Scale factor used instead of the one derived from the monitor DPI.
+  pub forced_scale_factor: Option<f64>,
+
+  /// The minimized state last reported with `WindowEvent::Minimized`.
+  pub minimized: bool,
+  /// The mode last reported with `WindowEvent::WindowModeChanged`.
+  pub window_mode: WindowMode,
+
   pub modifiers_state: ModifiersState,
   pub fullscreen: Option<Fullscreen>,
   pub current_theme: Theme,
   pub preferred_theme: Option<Theme>,
+  /// The theme inherited from the owner or parent window, used when `preferred_theme` is `None`.
+  pub inherited_theme: Option<Theme>,
 
   pub ime_handler: MinimalIme,
+  pub ime_allowed: bool,
 
   pub window_flags: WindowFlags,
 
@@ -139,19 +173,31 @@ impl WindowState {
 
       window_icon: attributes.window_icon.clone(),
       taskbar_icon,
+      menu: None,
 
       saved_window: None,
+      saved_maximized_window: None,
       scale_factor,
 
       dragging: false,
 
       skip_taskbar: false,
+      intercept_alt_f4: false,
+      drag_region: None,
+      resize_border_thickness: None,
+      hit_test_handler: None,
+      forced_scale_factor: None,
+
+      minimized: false,
+      window_mode: WindowMode::Normal,
 
       modifiers_state: ModifiersState::default(),
       fullscreen: None,
       current_theme,
       preferred_theme,
+      inherited_theme: None,
       ime_handler: MinimalIme::default(),
+      ime_allowed: true,
       window_flags: WindowFlags::empty(),
       is_active: false,
       is_focused: false,
@@ -205,6 +251,11 @@ impl MouseProperties {
     self.cursor_flags
   }
 
+  /// Re-applies the current cursor flags, e.g. after the OS reset the cursor clip.
+  pub fn refresh_os_cursor(&self, window: HWND) -> Result<(), io::Error> {
+    self.cursor_flags.refresh_os_cursor(window)
+  }
+
   pub fn set_cursor_flags<F>(&mut self, window: HWND, f: F) -> Result<(), io::Error>
   where
     F: FnOnce(&mut CursorFlags),
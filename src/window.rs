@@ -3,7 +3,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! The `Window` struct and associated types.
-use std::fmt;
+use std::{
+  collections::HashMap,
+  fmt,
+  sync::{Arc, Mutex},
+  time::Duration,
+};
 
 use crate::{
   dpi::{LogicalSize, PhysicalPosition, PhysicalSize, Pixel, PixelUnit, Position, Size},
@@ -65,6 +70,8 @@ pub struct ProgressBarState {
 /// ```
 pub struct Window {
   pub(crate) window: platform_impl::Window,
+  /// The resize debounces of the event loop, see [`Window::set_resize_debounce`].
+  pub(crate) resize_debounces: Arc<Mutex<HashMap<WindowId, Duration>>>,
 }
 
 impl fmt::Debug for Window {
@@ -82,6 +89,7 @@ impl Drop for Window {
     if let Some(Fullscreen::Exclusive(_)) = self.fullscreen() {
       self.set_fullscreen(None);
     }
+    self.set_resize_debounce(None);
   }
 }
 
@@ -163,6 +171,17 @@ pub struct WindowAttributes {
   /// [`Window::set_outer_position`]: crate::window::Window::set_outer_position
   pub position: Option<Position>,
 
+  /// Whether `position` is moved onto the work area of the nearest monitor when the top of the
+  /// window wouldn't be on any monitor's work area there, e.g. because the monitor a saved
+  /// position belongs to is gone.
+  ///
+  /// The default is `false`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android / iOS / Linux Wayland:** Unsupported.
+  pub position_clamped: bool,
+
   /// Whether the window is resizable or not.
   ///
   /// The default is `true`.
@@ -230,6 +249,13 @@ pub struct WindowAttributes {
   /// The default is `false`.
   pub always_on_bottom: bool,
 
+  /// The window the new window is placed directly below in the z-order.
+  ///
+  /// The default is `None`, which places it above the other windows.
+  ///
+  /// See [`WindowBuilder::with_below`] for details.
+  pub below: Option<WindowId>,
+
   /// The window icon.
   ///
   /// The default is `None`.
@@ -257,6 +283,20 @@ pub struct WindowAttributes {
   ///
   /// - **iOS / Android / Windows:** Unsupported.
   pub visible_on_all_workspaces: bool,
+
+  /// The minimum interval between two `WindowEvent::Resized` events.
+  ///
+  /// The default is `None`.
+  ///
+  /// See [`Window::set_resize_debounce`] for details.
+  pub resize_debounce: Option<Duration>,
+
+  /// The thickness of the edges that resize an undecorated window, in logical pixels.
+  ///
+  /// The default is `None`, which uses the platform's default thickness.
+  ///
+  /// See [`Window::set_resize_border_thickness`] for details.
+  pub resize_border_thickness: Option<f64>,
 }
 
 impl Default for WindowAttributes {
@@ -266,6 +306,7 @@ impl Default for WindowAttributes {
       inner_size: None,
       inner_size_constraints: Default::default(),
       position: None,
+      position_clamped: false,
       resizable: true,
       minimizable: true,
       maximizable: true,
@@ -278,11 +319,14 @@ impl Default for WindowAttributes {
       decorations: true,
       always_on_top: false,
       always_on_bottom: false,
+      below: None,
       window_icon: None,
       preferred_theme: None,
       focused: true,
       content_protection: false,
       visible_on_all_workspaces: false,
+      resize_debounce: None,
+      resize_border_thickness: None,
     }
   }
 }
@@ -352,6 +396,24 @@ impl WindowBuilder {
   #[inline]
   pub fn with_position<P: Into<Position>>(mut self, position: P) -> Self {
     self.window.position = Some(position.into());
+    self.window.position_clamped = false;
+    self
+  }
+
+  /// Sets a desired initial position for the window, moved onto the work area of the nearest
+  /// monitor if the top of the window wouldn't be visible there.
+  ///
+  /// This is meant for restoring saved window positions, which may be off-screen after a monitor
+  /// was unplugged or the layout of the monitors changed.
+  ///
+  /// See [`WindowAttributes::position`] and [`WindowAttributes::position_clamped`] for details.
+  ///
+  /// [`WindowAttributes::position`]: crate::window::WindowAttributes::position
+  /// [`WindowAttributes::position_clamped`]: crate::window::WindowAttributes::position_clamped
+  #[inline]
+  pub fn with_position_clamped<P: Into<Position>>(mut self, position: P) -> Self {
+    self.window.position = Some(position.into());
+    self.window.position_clamped = true;
     self
   }
 
@@ -485,6 +547,23 @@ impl WindowBuilder {
     self
   }
 
+  /// Places the window directly below the window with the given id when it is first shown,
+  /// instead of above all the other windows, e.g. to restore the stacking order of a session.
+  ///
+  /// A window placed below another one isn't focused when it is shown, regardless of
+  /// [`WindowBuilder::with_focused`]. This only applies to windows that are visible on creation,
+  /// and is ignored if the other window has been closed.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Only supported on X11.
+  /// - **iOS / Android:** Unsupported.
+  #[inline]
+  pub fn with_below(mut self, window_id: WindowId) -> Self {
+    self.window.below = Some(window_id);
+    self
+  }
+
   /// Sets the window icon.
   ///
   /// See [`Window::set_window_icon`] for details.
@@ -516,9 +595,17 @@ impl WindowBuilder {
 
   /// Whether the window will be initially focused or not.
   ///
+  /// A visible window created focused, which is the default, receives a
+  /// [`WindowEvent::Focused(true)`] once it is shown and the system lets it take the focus. A
+  /// window created unfocused doesn't receive any until it is focused later on.
+  ///
   /// ## Platform-specific:
   ///
-  /// **Android / iOS:** Unsupported.
+  /// - **Windows:** A window can't take the focus from the foreground application unless it is
+  ///   fullscreen, in which case it is forced to the foreground.
+  /// - **Android / iOS:** Unsupported.
+  ///
+  /// [`WindowEvent::Focused(true)`]: crate::event::WindowEvent::Focused
   #[inline]
   pub fn with_focused(mut self, focused: bool) -> WindowBuilder {
     self.window.focused = focused;
@@ -526,6 +613,9 @@ impl WindowBuilder {
   }
   /// Prevents the window contents from being captured by other apps.
   ///
+  /// The protection is applied before the window is first shown, so its contents are never
+  /// capturable. The default is `false`.
+  ///
   /// ## Platform-specific
   ///
   /// - **iOS / Android / Linux:** Unsupported.
@@ -546,18 +636,51 @@ impl WindowBuilder {
     self
   }
 
+  /// Coalesces rapid `WindowEvent::Resized` events.
+  ///
+  /// See [`Window::set_resize_debounce`] for details.
+  #[inline]
+  pub fn with_resize_debounce(mut self, debounce: Duration) -> WindowBuilder {
+    self.window.resize_debounce = Some(debounce);
+    self
+  }
+
+  /// Sets the thickness of the edges that resize the window while it is undecorated.
+  ///
+  /// See [`Window::set_resize_border_thickness`] for details.
+  #[inline]
+  pub fn with_resize_border_thickness(mut self, thickness: f64) -> WindowBuilder {
+    self.window.resize_border_thickness = Some(thickness);
+    self
+  }
+
   /// Builds the window.
   ///
   /// Possible causes of error include denied permission, incompatible system, and lack of memory.
   #[inline]
   pub fn build<T: 'static>(
-    self,
+    mut self,
     window_target: &EventLoopWindowTarget<T>,
   ) -> Result<Window, OsError> {
+    // focusing the window would bring it above the one it is placed below
+    if self.window.below.is_some() {
+      self.window.focused = false;
+    }
+
+    let resize_debounce = self.window.resize_debounce;
+    let resize_border_thickness = self.window.resize_border_thickness;
     platform_impl::Window::new(&window_target.p, self.window, self.platform_specific).map(
       |window| {
         window.request_redraw();
-        Window { window }
+        let window = Window {
+          window,
+          resize_debounces: window_target.resize_debouncer.debounces.clone(),
+        };
+        window.set_resize_debounce(resize_debounce);
+        if resize_border_thickness.is_some() {
+          window.set_resize_border_thickness(resize_border_thickness);
+        }
+        window
       },
     )
   }
@@ -598,6 +721,9 @@ impl Window {
   /// - **Android:** Always returns 1.0.
   /// - **iOS:** Can only be called on the main thread. Returns the underlying `UIView`'s
   ///   [`contentScaleFactor`].
+  /// - **Linux:** GTK 3 only supports integer scale factors. With fractional scaling (e.g. 125%),
+  ///   this is the scale factor rounded up and the compositor downscales the window, so physical
+  ///   sizes are those of the window buffer rather than of the screen.
   ///
   /// [`contentScaleFactor`]: https://developer.apple.com/documentation/uikit/uiview/1622657-contentscalefactor?language=objc
   #[inline]
@@ -605,6 +731,25 @@ impl Window {
     self.window.scale_factor()
   }
 
+  /// Coalesces rapid `WindowEvent::Resized` events, or stops coalescing them with `None`.
+  ///
+  /// While the window is being resized, at most one `Resized` event is delivered per `debounce`,
+  /// and the final size is delivered once the window hasn't been resized for `debounce`. This
+  /// avoids recreating rendering resources for every intermediate size.
+  ///
+  /// The event loop wakes up to deliver the final size, so a `ControlFlow::Wait` or a later
+  /// `ControlFlow::WaitUntil` is shortened in the meantime.
+  ///
+  /// The `Resized` event following a `WindowEvent::ScaleFactorChanged` is never held back.
+  #[inline]
+  pub fn set_resize_debounce(&self, debounce: Option<Duration>) {
+    let mut debounces = self.resize_debounces.lock().unwrap();
+    match debounce {
+      Some(debounce) => debounces.insert(self.id(), debounce),
+      None => debounces.remove(&self.id()),
+    };
+  }
+
   /// Emits a `WindowEvent::RedrawRequested` event in the associated event loop after all OS
   /// events have been processed by the event loop.
   ///
@@ -690,6 +835,8 @@ impl Window {
   ///
   /// - **iOS:** Can only be called on the main thread. Returns the `PhysicalSize` of the window's
   ///   [safe area] in screen space coordinates.
+  /// - **Android:** Returns the size of the area the activity is shown in, which is smaller than
+  ///   the screen in split-screen or freeform mode.
   ///
   /// [safe area]: https://developer.apple.com/documentation/uikit/uiview/2891103-safeareainsets?language=objc
   #[inline]
@@ -704,7 +851,9 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
-  /// - **iOS / Android:** Unsupported.
+  /// - **iOS / Android:** Unsupported, the size is chosen by the system. Changes to it, e.g. when
+  ///   moving to an external display or into split-screen, are reported with
+  ///   [`WindowEvent::Resized`](crate::event::WindowEvent::Resized).
   #[inline]
   pub fn set_inner_size<S: Into<Size>>(&self, size: S) {
     self.window.set_inner_size(size.into())
@@ -728,7 +877,7 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
-  /// - **iOS / Android:** Unsupported.
+  /// - **iOS / Android:** Unsupported, logs a warning.
   #[inline]
   pub fn set_min_inner_size<S: Into<Size>>(&self, min_size: Option<S>) {
     self.window.set_min_inner_size(min_size.map(|s| s.into()))
@@ -738,7 +887,7 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
-  /// - **iOS / Android:** Unsupported.
+  /// - **iOS / Android:** Unsupported, logs a warning.
   #[inline]
   pub fn set_max_inner_size<S: Into<Size>>(&self, max_size: Option<S>) {
     self.window.set_max_inner_size(max_size.map(|s| s.into()))
@@ -748,7 +897,7 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
-  /// - **iOS / Android:** Unsupported.
+  /// - **iOS / Android:** Unsupported, logs a warning.
   #[inline]
   pub fn set_inner_size_constraints(&self, constraints: WindowSizeConstraints) {
     self.window.set_inner_size_constraints(constraints)
@@ -771,7 +920,7 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
-  /// - **iOS / Android:** Unsupported. Returns ane empty string.
+  /// - **iOS / Android:** Unsupported. Returns an empty string.
   #[inline]
   pub fn title(&self) -> String {
     self.window.title()
@@ -782,6 +931,9 @@ impl Window {
   /// If `false`, this will hide the window. If `true`, this will show the window.
   /// ## Platform-specific
   ///
+  /// - **Linux:** The child widgets of the window are shown along with it the first time only,
+  ///   afterwards those the app hid, like a menu bar, are left hidden. Child windows are separate
+  ///   windows and keep their own visibility.
   /// - **Android:** Unsupported.
   /// - **iOS:** Can only be called on the main thread.
   #[inline]
@@ -799,6 +951,22 @@ impl Window {
     self.window.set_focus()
   }
 
+  /// Shows the window, restores it if it is minimized, then brings it to front and focuses it.
+  ///
+  /// Unlike [`Window::set_focus`], which does nothing for hidden or minimized windows, this does
+  /// all of it in one go, e.g. to show the main window of an application again.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** Uses the same workaround as window creation to take the foreground from
+  ///   other applications.
+  /// - **Linux:** The window manager may still only mark the window as demanding attention.
+  /// - **iOS / Android:** Unsupported.
+  #[inline]
+  pub fn activate(&self) {
+    self.window.activate()
+  }
+
   /// Is window active and focused?
   ///
   /// ## Platform-specific
@@ -809,6 +977,37 @@ impl Window {
     self.window.is_focused()
   }
 
+  /// Returns the id of the window that owns this window, if any.
+  ///
+  /// Owned windows stay on top of their owner and are closed along with it, emitting
+  /// [`WindowEvent::Destroyed`](crate::event::WindowEvent::Destroyed). They are created with
+  /// `WindowBuilderExtWindows::with_owner_window`, `WindowBuilderExtMacOS::with_parent_window` or
+  /// `WindowBuilderExtUnix::with_transient_for`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** Child windows created with `WindowBuilderExtWindows::with_parent_window` have no owner.
+  /// - **iOS / Android:** Unsupported, always returns `None`.
+  #[inline]
+  pub fn owner(&self) -> Option<WindowId> {
+    self.window.owner().map(WindowId)
+  }
+
+  /// Returns the ids of the windows owned by this window. See [`Window::owner`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS / Android:** Unsupported, always returns an empty list.
+  #[inline]
+  pub fn owned_windows(&self) -> Vec<WindowId> {
+    self
+      .window
+      .owned_windows()
+      .into_iter()
+      .map(WindowId)
+      .collect()
+  }
+
   /// Indicates whether the window is always on top of other windows.
   ///
   /// ## Platform-specific
@@ -848,7 +1047,8 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
-  /// - **Linux / iOS / Android:** Unsupported.
+  /// - **Linux:** Hints the window manager to remove its minimize action, which it may ignore.
+  /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn set_minimizable(&self, minimizable: bool) {
     self.window.set_minimizable(minimizable)
@@ -859,7 +1059,9 @@ impl Window {
   /// ## Platform-specific
   ///
   /// - **macOS:** Disables the "zoom" button in the window titlebar, which is also used to enter fullscreen mode.
-  /// - **Linux / iOS / Android:** Unsupported.
+  /// - **Linux:** Hints the window manager to remove its maximize action, which it may ignore. Non
+  ///   resizable windows are never offered it.
+  /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn set_maximizable(&self, maximizable: bool) {
     self.window.set_maximizable(maximizable)
@@ -881,6 +1083,8 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
+  /// - **macOS:** Minimizing is ignored while the window is in native (borderless) fullscreen,
+  ///   leave fullscreen first.
   /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn set_minimized(&self, minimized: bool) {
@@ -889,9 +1093,21 @@ impl Window {
 
   /// Sets the window to maximized or back.
   ///
+  /// The new size and position are reported with [`WindowEvent::Resized`] and
+  /// [`WindowEvent::Moved`], and [`WindowEvent::WindowModeChanged`] is emitted once the window is
+  /// done switching.
+  ///
   /// ## Platform-specific
   ///
+  /// - **Windows:** Takes effect before returning when called from the event loop thread, so
+  ///   `inner_size` and `is_maximized` already reflect it. From other threads, the change is
+  ///   applied asynchronously by the event loop thread and these getters return the previous
+  ///   state until then.
   /// - **iOS / Android:** Unsupported.
+  ///
+  /// [`WindowEvent::Resized`]: crate::event::WindowEvent::Resized
+  /// [`WindowEvent::Moved`]: crate::event::WindowEvent::Moved
+  /// [`WindowEvent::WindowModeChanged`]: crate::event::WindowEvent::WindowModeChanged
   #[inline]
   pub fn set_maximized(&self, maximized: bool) {
     self.window.set_maximized(maximized)
@@ -941,7 +1157,7 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
-  /// - **Linux / iOS / Android:** Unsupported.
+  /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn is_minimizable(&self) -> bool {
     self.window.is_minimizable()
@@ -951,7 +1167,7 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
-  /// - **Linux / iOS / Android:** Unsupported.
+  /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn is_maximizable(&self) -> bool {
     self.window.is_maximizable()
@@ -984,7 +1200,8 @@ impl Window {
   ///   video mode change. *Caveat!* macOS doesn't provide task switching (or
   ///   spaces!) while in exclusive fullscreen mode. This mode should be used
   ///   when a video mode change is desired, but for a better user experience,
-  ///   borderless fullscreen might be preferred.
+  ///   borderless fullscreen might be preferred. The display is released and its
+  ///   original video mode restored when leaving fullscreen or dropping the window.
   ///
   ///   `Fullscreen::Borderless` provides a borderless fullscreen window on a
   ///   separate space. This is the idiomatic way for fullscreen games to work
@@ -992,10 +1209,17 @@ impl Window {
   ///   separate spaces are not preferred.
   ///
   ///   The dock and the menu bar are always disabled in fullscreen mode.
+  ///
+  ///   Entering and leaving `Fullscreen::Borderless` is animated and completes asynchronously.
+  ///   [`WindowEvent::WindowModeChanged`] is emitted once the transition has finished, whether it
+  ///   was started here or by the user.
   /// - **iOS:** Can only be called on the main thread.
   /// - **Windows:** Screen saver is disabled in fullscreen mode.
-  /// - **Linux:** The window will only fullscreen to current monitor no matter which enum variant.
+  /// - **Linux:** The window will only fullscreen to current monitor no matter which enum variant,
+  ///   except `Fullscreen::BorderlessWorkArea` which honors the given monitor.
   /// - **Android:** Unsupported.
+  ///
+  /// [`WindowEvent::WindowModeChanged`]: crate::event::WindowEvent::WindowModeChanged
   #[inline]
   pub fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
     self.window.set_fullscreen(fullscreen)
@@ -1005,8 +1229,13 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
+  /// - **macOS:** While entering fullscreen, this already returns the fullscreen state being
+  ///   entered. While leaving it, this keeps returning it until the transition has finished and
+  ///   [`WindowEvent::WindowModeChanged`] is emitted.
   /// - **iOS:** Can only be called on the main thread.
   /// - **Android:** Will always return `None`.
+  ///
+  /// [`WindowEvent::WindowModeChanged`]: crate::event::WindowEvent::WindowModeChanged
   #[inline]
   pub fn fullscreen(&self) -> Option<Fullscreen> {
     self.window.fullscreen()
@@ -1037,6 +1266,8 @@ impl Window {
 
   /// Change whether or not the window will always be on top of other windows.
   ///
+  /// This can be called while the window is hidden, the window is kept on top once it is shown.
+  ///
   /// ## Platform-specific
   ///
   /// - **iOS / Android:** Unsupported.
@@ -1054,11 +1285,35 @@ impl Window {
   ///
   /// On Windows, this sets `ICON_SMALL`. The base size for a window icon is 16x16, but it's
   /// recommended to account for screen scaling and pick a multiple of that, i.e. 32x32.
+  ///
+  /// On Linux Wayland, compositors usually ignore this icon for the taskbar and app switcher
+  /// and use the icon of the `.desktop` file matching the app id set with
+  /// `EventLoopBuilderExtUnix::with_app_id` instead. A window without an icon has its icon name
+  /// set to the app id, so compositors that look icons up by name find the right one.
+  ///
+  /// Passing `None` removes the icon set by tao and reverts to the default icon, which is the
+  /// first icon embedded in the executable on Windows (or the generic application icon if it has
+  /// none) and the application or theme icon on Linux.
+  ///
+  /// The icon is already converted to the platform format by [`Icon::from_rgba`], which reports
+  /// invalid icon data as a [`BadIcon`] error, so there is nothing left to fail here. Decoding
+  /// image files should be done by the application before that, off the main thread if needed.
   #[inline]
   pub fn set_window_icon(&self, window_icon: Option<Icon>) {
     self.window.set_window_icon(window_icon)
   }
 
+  /// Returns the window icon last set with [`Window::set_window_icon`] or
+  /// [`WindowBuilder::with_window_icon`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS / Android / macOS:** Always returns `None`.
+  #[inline]
+  pub fn window_icon(&self) -> Option<Icon> {
+    self.window.window_icon()
+  }
+
   /// Sets location of IME candidate box in client area coordinates relative to the top left.
   ///
   /// ## Platform-specific
@@ -1069,6 +1324,21 @@ impl Window {
     self.window.set_ime_position(position.into())
   }
 
+  /// Sets whether the window accepts IME input.
+  ///
+  /// When disabled, no composition happens and [`WindowEvent::ReceivedImeText`] is not emitted,
+  /// which is useful while a game or 3D view has keyboard focus. IME is allowed by default.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS / Android:** Unsupported.
+  ///
+  /// [`WindowEvent::ReceivedImeText`]: crate::event::WindowEvent::ReceivedImeText
+  #[inline]
+  pub fn set_ime_allowed(&self, allowed: bool) {
+    self.window.set_ime_allowed(allowed)
+  }
+
   /// Sets the taskbar progress state.
   ///
   /// ## Platform-specific
@@ -1121,6 +1391,11 @@ impl Window {
   /// ## Platform-specific
   ///
   /// - **Linux / macOS**: Theme is app-wide and not specific to this window.
+  /// - **Linux:** [`Window::theme`] returns the new theme right away on this window. With `None`,
+  ///   and on the other windows, it is updated once the GTK settings change is applied on the
+  ///   event loop, right before [`WindowEvent::ThemeChanged`](crate::event::WindowEvent::ThemeChanged)
+  ///   is emitted.
+  /// - **Windows:** Owned and child windows that have no theme of their own follow this window's theme.
   /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn set_theme(&self, #[allow(unused)] theme: Option<Theme>) {
@@ -1138,6 +1413,10 @@ impl Window {
 
   /// Prevents the window contents from being captured by other apps.
   ///
+  /// The protection is set on this native window only, windows that are already owned by it are
+  /// not affected, see [`Window::set_content_protection_with_owned_windows`]. Windows created
+  /// later with this window as their owner are protected if this window is.
+  ///
   /// ## Platform-specific
   ///
   /// - **iOS / Android / Linux:** Unsupported.
@@ -1146,6 +1425,36 @@ impl Window {
     self.window.set_content_protection(enabled);
   }
 
+  /// Like [`Window::set_content_protection`], but also applies to the windows currently owned by
+  /// this window, and the windows they own. See [`Window::owned_windows`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS / Android / Linux:** Unsupported.
+  pub fn set_content_protection_with_owned_windows(&self, #[allow(unused)] enabled: bool) {
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    self
+      .window
+      .set_content_protection_with_owned_windows(enabled);
+  }
+
+  /// Returns whether the window contents are protected from being captured by other apps.
+  ///
+  /// This reads the state of the native window, which is unprotected unless protection was
+  /// enabled with [`WindowBuilder::with_content_protection`], [`Window::set_content_protection`]
+  /// or inherited from the owner window.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS / Android / Linux:** Unsupported, always returns `false`.
+  pub fn is_content_protected(&self) -> bool {
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    let protected = self.window.is_content_protected();
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let protected = false;
+    protected
+  }
+
   /// Sets whether the window should be visible on all workspaces.
   ///
   /// ## Platform-specific
@@ -1163,13 +1472,20 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
+  /// - **Windows:** The cursor icon is only shown over this window. Owned and child windows keep
+  ///   their own, which is `CursorIcon::Default` unless set on them too.
   /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn set_cursor_icon(&self, cursor: CursorIcon) {
     self.window.set_cursor_icon(cursor);
   }
 
-  /// Changes the position of the cursor in window coordinates.
+  /// Changes the position of the cursor in window coordinates, relative to the top-left corner
+  /// of the client area.
+  ///
+  /// This is the same as [`Window::set_cursor_position_window`]. Note that this isn't the
+  /// coordinate space of [`Window::outer_position`], use [`Window::set_cursor_position_screen`]
+  /// for that.
   ///
   /// ## Platform-specific
   ///
@@ -1179,6 +1495,34 @@ impl Window {
     self.window.set_cursor_position(position.into())
   }
 
+  /// Changes the position of the cursor relative to the top-left corner of the client area of
+  /// the window.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS / Android:** Always returns an [`ExternalError::NotSupported`].
+  #[inline]
+  pub fn set_cursor_position_window<P: Into<Position>>(
+    &self,
+    position: P,
+  ) -> Result<(), ExternalError> {
+    self.window.set_cursor_position(position.into())
+  }
+
+  /// Changes the position of the cursor in screen coordinates, which is the coordinate space of
+  /// [`Window::outer_position`] and [`Window::set_outer_position`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS / Android:** Always returns an [`ExternalError::NotSupported`].
+  #[inline]
+  pub fn set_cursor_position_screen<P: Into<Position>>(
+    &self,
+    position: P,
+  ) -> Result<(), ExternalError> {
+    self.window.set_cursor_position_screen(position.into())
+  }
+
   /// Grabs the cursor, preventing it from leaving the window.
   ///
   /// There's no guarantee that the cursor will be hidden. You should
@@ -1187,6 +1531,8 @@ impl Window {
   /// ## Platform-specific
   ///
   /// - **macOS:** This locks the cursor in a fixed location, which looks visually awkward.
+  /// - **Windows:** The cursor is released while the window is unfocused and confined again
+  ///   once it regains focus.
   /// - **iOS / Android:** Always returns an [`ExternalError::NotSupported`].
   #[inline]
   pub fn set_cursor_grab(&self, grab: bool) -> Result<(), ExternalError> {
@@ -1235,6 +1581,77 @@ impl Window {
     self.window.drag_resize_window(direction)
   }
 
+  /// Sets the thickness of the edges that resize the window while it is undecorated and
+  /// resizable, in logical pixels. `None` restores the platform's default thickness.
+  ///
+  /// The edges are inside the window, so they cover the outer part of its content. Thicker
+  /// edges are easier to grab, e.g. on touch screens.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** The default is the thickness of the system's resize frame.
+  /// - **Linux:** The default is 5 pixels.
+  /// - **macOS / iOS / Android:** Unsupported.
+  #[inline]
+  pub fn set_resize_border_thickness(&self, #[allow(unused)] thickness: Option<f64>) {
+    #[cfg(any(
+      windows,
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    ))]
+    self.window.set_resize_border_thickness(thickness)
+  }
+
+  /// Makes a region of the client area move the window when dragged with the left mouse button,
+  /// like a title bar, so undecorated windows can draw their own. `None` removes it.
+  ///
+  /// The region is relative to the top left of the client area. Presses inside it aren't
+  /// reported to the application.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** Double-clicking the region maximizes or restores the window.
+  /// - **iOS / Android:** Unsupported.
+  #[inline]
+  pub fn set_drag_region(&self, region: Option<(Position, Size)>) {
+    self.window.set_drag_region(region)
+  }
+
+  /// Sets a handler deciding how the window reacts to the left mouse button at a point of its
+  /// client area, so undecorated windows can draw their own title bar and resize edges.
+  /// `None` removes it.
+  ///
+  /// The handler receives the position relative to the top left of the client area and is called
+  /// on the event loop thread, so it should return quickly. While a handler is set, it replaces
+  /// the resize edges of undecorated windows and the region set with [`Window::set_drag_region`].
+  /// [`HitTestResult::Resize`] is ignored if the window isn't resizable or is maximized.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** The handler answers `WM_NCHITTEST`, so the system treats [`HitTestResult::Drag`]
+  ///   like a title bar, e.g. double-clicking it maximizes or restores the window.
+  /// - **Linux:** The handler is only called when a button is pressed, and when the pointer moves
+  ///   over an undecorated window to show the resize cursors.
+  /// - **macOS / iOS / Android:** Unsupported.
+  #[inline]
+  pub fn set_hit_test_handler(
+    &self,
+    #[allow(unused)] handler: Option<Box<dyn Fn(PhysicalPosition<f64>) -> HitTestResult + Send>>,
+  ) {
+    #[cfg(any(
+      windows,
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    ))]
+    self.window.set_hit_test_handler(handler)
+  }
+
   /// Modifies whether the window catches cursor events.
   ///
   /// If `true`, the events are passed through the window such that any other window behind it receives them.
@@ -1419,6 +1836,24 @@ impl Default for CursorIcon {
   }
 }
 
+/// The mode a window is shown in, see [`WindowEvent::WindowModeChanged`].
+///
+/// [`WindowEvent::WindowModeChanged`]: crate::event::WindowEvent::WindowModeChanged
+#[non_exhaustive]
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum WindowMode {
+  /// The window is shown at its normal size and position.
+  #[default]
+  Normal,
+  /// The window is maximized, see [`Window::set_maximized`].
+  Maximized,
+  /// The window covers a monitor, see [`Window::set_fullscreen`].
+  Fullscreen,
+  /// The window is minimized, see [`Window::set_minimized`].
+  Minimized,
+}
+
 /// Fullscreen modes.
 #[non_exhaustive]
 #[allow(clippy::large_enum_variant)]
@@ -1428,8 +1863,25 @@ pub enum Fullscreen {
 
   /// Providing `None` to `Borderless` will fullscreen on the current monitor.
   Borderless(Option<MonitorHandle>),
+
+  /// Like `Borderless`, but only covers the work area of the monitor, leaving the taskbar,
+  /// dock or panels visible.
+  ///
+  /// Providing `None` will use the current monitor.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS:** The window covers the visible frame of the screen instead of moving to a
+  ///   separate space.
+  /// - **Linux (Wayland):** The window is resized to the work area but can't be moved there.
+  /// - **iOS:** Same as `Borderless`.
+  /// - **Android:** Unsupported.
+  BorderlessWorkArea(Option<MonitorHandle>),
 }
 
+/// A color, as red, green, blue and alpha components.
+pub type RGBA = (u8, u8, u8, u8);
+
 #[non_exhaustive]
 #[derive(Default, Clone, Copy, Debug, PartialEq)]
 pub enum Theme {
@@ -1587,6 +2039,73 @@ pub enum ResizeDirection {
   West,
 }
 
+/// How the window reacts to the left mouse button at a point, see [`Window::set_hit_test_handler`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum HitTestResult {
+  /// The point is part of the client area and the application receives the press.
+  Client,
+  /// Dragging from the point moves the window, like a title bar.
+  Drag,
+  /// Dragging from the point resizes the window in the given direction.
+  Resize(ResizeDirection),
+}
+
+#[cfg(any(
+  windows,
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd",
+))]
+pub(crate) type HitTestHandler = Box<dyn Fn(PhysicalPosition<f64>) -> HitTestResult + Send>;
+
+/// Returns the top-left corner to move a window whose outer frame is `(left, top, right, bottom)`
+/// to so that the top of the window is on one of the `work_areas`, or `None` when it already is.
+/// The window is moved onto the work area closest to the middle of its top edge.
+///
+/// The frame and the work areas must share the same coordinate space, with y increasing downwards,
+/// and `scale_factor` converts logical pixels to its units.
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+pub(crate) fn clamp_frame_to_work_areas(
+  (left, top, right, bottom): (f64, f64, f64, f64),
+  work_areas: &[(f64, f64, f64, f64)],
+  scale_factor: f64,
+) -> Option<(f64, f64)> {
+  // Height of the top of the window that must be on a work area, in logical pixels.
+  const VISIBLE_TOP: f64 = 32.0;
+
+  let (width, height) = (right - left, bottom - top);
+  let visible_top = (VISIBLE_TOP * scale_factor).min(height);
+
+  let is_visible = work_areas
+    .iter()
+    .any(|&(area_left, area_top, area_right, area_bottom)| {
+      left.max(area_left) < right.min(area_right)
+        && top >= area_top
+        && top + visible_top <= area_bottom
+    });
+  if is_visible {
+    return None;
+  }
+
+  let distance = |&&(area_left, area_top, area_right, area_bottom): &&(f64, f64, f64, f64)| {
+    let (anchor_x, anchor_y) = (left + width / 2.0, top);
+    let dx = anchor_x - anchor_x.clamp(area_left, area_right);
+    let dy = anchor_y - anchor_y.clamp(area_top, area_bottom);
+    dx * dx + dy * dy
+  };
+  work_areas
+    .iter()
+    .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+    .map(|&(area_left, area_top, area_right, area_bottom)| {
+      (
+        left.clamp(area_left, (area_right - width).max(area_left)),
+        top.clamp(area_top, (area_bottom - height).max(area_top)),
+      )
+    })
+}
+
 pub(crate) fn hit_test(
   (left, top, right, bottom): (i32, i32, i32, i32),
   cx: i32,
@@ -1621,3 +2140,71 @@ pub(crate) fn hit_test(
     _ => None,
   }
 }
+
+#[cfg(test)]
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod tests {
+  use super::*;
+
+  const PRIMARY: (f64, f64, f64, f64) = (0.0, 0.0, 1920.0, 1040.0);
+  const SECONDARY: (f64, f64, f64, f64) = (1920.0, 0.0, 3840.0, 1040.0);
+
+  #[test]
+  fn visible_frame_is_kept() {
+    let frame = (100.0, 100.0, 500.0, 400.0);
+    assert_eq!(clamp_frame_to_work_areas(frame, &[PRIMARY], 1.0), None);
+    // a frame spanning both monitors is visible as well
+    let frame = (1800.0, 100.0, 2200.0, 400.0);
+    assert_eq!(
+      clamp_frame_to_work_areas(frame, &[PRIMARY, SECONDARY], 1.0),
+      None
+    );
+  }
+
+  #[test]
+  fn off_screen_frame_is_moved_to_the_nearest_work_area() {
+    let areas = [PRIMARY, SECONDARY];
+    let frame = (-3000.0, -3000.0, -2600.0, -2700.0);
+    assert_eq!(
+      clamp_frame_to_work_areas(frame, &areas, 1.0),
+      Some((0.0, 0.0))
+    );
+    let frame = (5000.0, 200.0, 5400.0, 500.0);
+    assert_eq!(
+      clamp_frame_to_work_areas(frame, &areas, 1.0),
+      Some((3440.0, 200.0))
+    );
+  }
+
+  #[test]
+  fn partly_visible_title_bar_is_moved_onto_the_work_area() {
+    // above the work area
+    let frame = (100.0, -10.0, 500.0, 290.0);
+    assert_eq!(
+      clamp_frame_to_work_areas(frame, &[PRIMARY], 1.0),
+      Some((100.0, 0.0))
+    );
+    // only 20 of the 32 logical pixels at the top are on the work area
+    let frame = (100.0, 1020.0, 500.0, 1320.0);
+    assert_eq!(
+      clamp_frame_to_work_areas(frame, &[PRIMARY], 1.0),
+      Some((100.0, 740.0))
+    );
+    // which are 64 physical pixels at a scale factor of 2
+    let frame = (100.0, 990.0, 500.0, 1290.0);
+    assert_eq!(clamp_frame_to_work_areas(frame, &[PRIMARY], 1.0), None);
+    assert_eq!(
+      clamp_frame_to_work_areas(frame, &[PRIMARY], 2.0),
+      Some((100.0, 740.0))
+    );
+  }
+
+  #[test]
+  fn frame_larger_than_the_work_area_is_aligned_to_its_top_left() {
+    let frame = (-50.0, -50.0, 2950.0, 1950.0);
+    assert_eq!(
+      clamp_frame_to_work_areas(frame, &[PRIMARY], 1.0),
+      Some((0.0, 0.0))
+    );
+  }
+}
@@ -9,7 +9,7 @@ use tao::{
   dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize},
   event::{ElementState, MouseButton, MouseScrollDelta, TouchPhase},
   keyboard::{Key, KeyCode, KeyLocation, ModifiersState},
-  window::CursorIcon,
+  window::{CursorIcon, WindowMode},
 };
 
 #[allow(dead_code)]
@@ -18,6 +18,7 @@ fn needs_serde<S: Serialize + Deserialize<'static>>() {}
 #[test]
 fn window_serde() {
   needs_serde::<CursorIcon>();
+  needs_serde::<WindowMode>();
 }
 
 #[test]
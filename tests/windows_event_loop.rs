@@ -0,0 +1,390 @@
+// Copyright 2014-2021 The winit contributors
+// Copyright 2021-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(target_os = "windows")]
+
+use std::{
+  collections::HashSet,
+  time::{Duration, Instant},
+};
+
+use tao::{
+  dpi::{PhysicalPosition, PhysicalSize},
+  event::{Event, StartCause, WindowEvent},
+  event_loop::{ControlFlow, EventLoop, EventLoopBuilder},
+  platform::{
+    run_return::EventLoopExtRunReturn,
+    windows::{EventLoopBuilderExtWindows, WindowBuilderExtWindows, WindowExtWindows},
+  },
+  window::{Window, WindowBuilder},
+};
+
+fn event_loop() -> EventLoop<()> {
+  EventLoopBuilder::new().with_any_thread(true).build()
+}
+
+fn hidden_window(event_loop: &EventLoop<()>, builder: WindowBuilder) -> Window {
+  builder.with_visible(false).build(event_loop).unwrap()
+}
+
+/// Creates a window from `builder` once the event loop is running, then calls `step` with the
+/// window and the index of the step every `interval`, until it returns `false`.
+///
+/// The events of the window are passed to `on_event` along with the number of steps started so
+/// far.
+fn run_window_steps(
+  builder: WindowBuilder,
+  interval: Duration,
+  mut step: impl FnMut(&Window, usize) -> bool,
+  mut on_event: impl FnMut(&Window, usize, WindowEvent<'_>),
+) {
+  let mut event_loop = event_loop();
+  let mut window = None;
+  let mut steps = 0;
+
+  event_loop.run_return(|event, target, control_flow| match event {
+    Event::NewEvents(StartCause::Init) => {
+      window = Some(builder.clone().build(target).unwrap());
+      *control_flow = ControlFlow::WaitUntil(Instant::now() + interval);
+    }
+    Event::NewEvents(StartCause::ResumeTimeReached { .. }) => {
+      // counted before running the step, so the events it triggers are passed on as following it
+      steps += 1;
+      let keep_going = step(window.as_ref().unwrap(), steps - 1);
+      *control_flow = if keep_going {
+        ControlFlow::WaitUntil(Instant::now() + interval)
+      } else {
+        ControlFlow::Exit
+      };
+    }
+    Event::WindowEvent {
+      event, window_id, ..
+    } if window.as_ref().map(|w| w.id()) == Some(window_id) => {
+      on_event(window.as_ref().unwrap(), steps, event)
+    }
+    _ => (),
+  });
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn run_return_returns_exit_code() {
+  let exit_code = event_loop().run_return(|event, _, control_flow| {
+    if let Event::NewEvents(StartCause::Init) = event {
+      *control_flow = ControlFlow::ExitWithCode(3);
+    }
+  });
+
+  assert_eq!(exit_code, 3);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn exit_code_is_kept_once_set() {
+  let mut destroyed_with = None;
+
+  let exit_code = event_loop().run_return(|event, _, control_flow| match event {
+    Event::NewEvents(StartCause::Init) => *control_flow = ControlFlow::ExitWithCode(3),
+    Event::MainEventsCleared => *control_flow = ControlFlow::ExitWithCode(4),
+    Event::LoopDestroyed => destroyed_with = Some(*control_flow),
+    _ => (),
+  });
+
+  assert_eq!(exit_code, 3);
+  assert_eq!(destroyed_with, Some(ControlFlow::ExitWithCode(3)));
+}
+
+#[cfg(feature = "test-util")]
+#[test]
+#[cfg_attr(miri, ignore)]
+fn pump_events_returns_between_iterations() {
+  use tao::platform::pump_events::{EventLoopExtPumpEvents, PumpStatus};
+
+  let mut event_loop = EventLoopBuilder::<u32>::with_user_event()
+    .with_any_thread(true)
+    .build();
+  let proxy = event_loop.create_proxy();
+
+  let mut init = false;
+  let status = event_loop.pump_events(Some(Duration::ZERO), |event, _, _| {
+    if let Event::NewEvents(StartCause::Init) = event {
+      init = true;
+    }
+  });
+  assert_eq!(status, PumpStatus::Continue);
+  assert!(init);
+
+  proxy.send_event(42).unwrap();
+  let mut received = None;
+  let status = event_loop.pump_events(Some(Duration::from_secs(1)), |event, _, control_flow| {
+    if let Event::UserEvent(value) = event {
+      received = Some(value);
+      *control_flow = ControlFlow::ExitWithCode(3);
+    }
+  });
+  assert_eq!(received, Some(42));
+  assert_eq!(status, PumpStatus::Exit(3));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn monitors_match_across_enumerations() {
+  let event_loop = event_loop();
+  let first: Vec<_> = event_loop.available_monitors().collect();
+  let second: Vec<_> = event_loop.available_monitors().collect();
+  assert_eq!(first, second);
+
+  let set: HashSet<_> = first.iter().cloned().collect();
+  assert_eq!(set.len(), first.len());
+  assert!(second.iter().all(|monitor| set.contains(monitor)));
+
+  if let Some(primary) = event_loop.primary_monitor() {
+    assert!(set.contains(&primary));
+  }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn current_video_mode_is_listed() {
+  for monitor in event_loop().available_monitors() {
+    let size = monitor.size();
+    let modes: Vec<_> = monitor.video_modes().collect();
+    assert!(
+      modes.iter().any(|mode| mode.size() == size),
+      "{:?} doesn't list its current size {:?}: {:?}",
+      monitor.name(),
+      size,
+      modes
+    );
+  }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn initial_size_is_clamped_to_max() {
+  let event_loop = event_loop();
+  let window = hidden_window(
+    &event_loop,
+    WindowBuilder::new()
+      .with_inner_size(PhysicalSize::new(2000, 2000))
+      .with_max_inner_size(PhysicalSize::new(800, 600)),
+  );
+
+  let size = window.inner_size();
+  assert!(size.width <= 800, "width {} exceeds max", size.width);
+  assert!(size.height <= 600, "height {} exceeds max", size.height);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn off_screen_position_is_moved_onto_a_monitor() {
+  let event_loop = event_loop();
+  let window = hidden_window(
+    &event_loop,
+    WindowBuilder::new().with_position_clamped(PhysicalPosition::new(-100_000, -100_000)),
+  );
+
+  let position = window.outer_position().unwrap();
+  assert!(event_loop.available_monitors().any(|monitor| {
+    let (origin, size) = (monitor.position(), monitor.size());
+    position.x >= origin.x
+      && position.y >= origin.y
+      && position.x < origin.x + size.width as i32
+      && position.y < origin.y + size.height as i32
+  }));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn visible_position_is_kept() {
+  let event_loop = event_loop();
+  let monitor = event_loop.primary_monitor().unwrap();
+  // the middle of the monitor is on its work area, wherever the taskbar is
+  let position = PhysicalPosition::new(
+    monitor.position().x + monitor.size().width as i32 / 2,
+    monitor.position().y + monitor.size().height as i32 / 2,
+  );
+  let window = hidden_window(
+    &event_loop,
+    WindowBuilder::new().with_position_clamped(position),
+  );
+
+  assert_eq!(window.outer_position().unwrap(), position);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn content_protection_is_off_by_default() {
+  let event_loop = event_loop();
+  let window = hidden_window(&event_loop, WindowBuilder::new());
+
+  assert!(!window.is_content_protected());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn content_protection_round_trips() {
+  let event_loop = event_loop();
+  let window = hidden_window(
+    &event_loop,
+    WindowBuilder::new().with_content_protection(true),
+  );
+  assert!(window.is_content_protected());
+
+  window.set_content_protection(false);
+  assert!(!window.is_content_protected());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn owner_lists_owned_windows() {
+  let event_loop = event_loop();
+  let owner = hidden_window(&event_loop, WindowBuilder::new());
+  let owned = hidden_window(
+    &event_loop,
+    WindowBuilder::new().with_owner_window(owner.hwnd()),
+  );
+
+  assert_eq!(owner.owner(), None);
+  assert_eq!(owned.owner(), Some(owner.id()));
+  assert_eq!(owner.owned_windows(), vec![owned.id()]);
+  assert!(owned.owned_windows().is_empty());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn focused_window_emits_initial_focus_once() {
+  let mut events = Vec::new();
+  let mut is_focused = false;
+
+  run_window_steps(
+    WindowBuilder::new().with_focused(true),
+    Duration::from_millis(500),
+    |window, _| {
+      is_focused = window.is_focused();
+      false
+    },
+    |_, _, event| {
+      if let WindowEvent::Focused(focused) = event {
+        events.push(focused);
+      }
+    },
+  );
+
+  assert_eq!(events, [true]);
+  assert!(is_focused);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn unfocused_window_emits_no_initial_focus() {
+  let mut events = Vec::new();
+  let mut is_focused = true;
+
+  run_window_steps(
+    WindowBuilder::new().with_focused(false),
+    Duration::from_millis(500),
+    |window, _| {
+      is_focused = window.is_focused();
+      false
+    },
+    |_, _, event| {
+      if let WindowEvent::Focused(focused) = event {
+        events.push(focused);
+      }
+    },
+  );
+
+  assert!(!events.contains(&true));
+  assert!(!is_focused);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn unmaximize_restores_geometry() {
+  let mut geometry = Vec::new();
+
+  run_window_steps(
+    WindowBuilder::new()
+      .with_position(PhysicalPosition::new(120, 140))
+      .with_inner_size(PhysicalSize::new(400, 300)),
+    Duration::from_millis(200),
+    |window, step| {
+      geometry.push((window.outer_position().unwrap(), window.inner_size()));
+      match step {
+        0 | 2 => window.set_maximized(true),
+        1 | 3 => window.set_maximized(false),
+        _ => return false,
+      }
+      true
+    },
+    |_, _, _| (),
+  );
+
+  // geometry before maximizing, then after each restore
+  let restored: Vec<_> = geometry.iter().step_by(2).collect();
+  assert_eq!(restored.len(), 3);
+  assert!(restored.iter().all(|g| *g == restored[0]), "{restored:?}");
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Recorded {
+  ScaleFactorChanged(f64),
+  Resized,
+}
+
+/// Forces a scale factor on a window created with `builder` once its creation events have been
+/// delivered, and returns the `ScaleFactorChanged` and `Resized` events that follow, along with
+/// the scale factor returned by the window while handling each of them.
+fn scale_factor_change_events(builder: WindowBuilder, scale_factor: f64) -> Vec<(Recorded, f64)> {
+  let mut events = Vec::new();
+
+  run_window_steps(
+    builder,
+    Duration::from_millis(200),
+    |window, step| {
+      if step == 0 {
+        window.force_scale_factor(Some(scale_factor));
+      }
+      step == 0
+    },
+    |window, steps, event| {
+      let recorded = match event {
+        _ if steps == 0 => return,
+        WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+          Recorded::ScaleFactorChanged(scale_factor)
+        }
+        WindowEvent::Resized(_) => Recorded::Resized,
+        _ => return,
+      };
+      events.push((recorded, window.scale_factor()));
+    },
+  );
+
+  events
+}
+
+fn assert_resized_after_scale_factor_changed(events: &[(Recorded, f64)], scale_factor: f64) {
+  assert_eq!(
+    events.first(),
+    Some(&(Recorded::ScaleFactorChanged(scale_factor), scale_factor))
+  );
+  assert!(events.contains(&(Recorded::Resized, scale_factor)));
+  assert!(events.iter().all(|(_, current)| *current == scale_factor));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn resized_follows_scale_factor_changed() {
+  let events = scale_factor_change_events(WindowBuilder::new(), 2.5);
+  assert_resized_after_scale_factor_changed(&events, 2.5);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn resized_follows_scale_factor_changed_without_size_change() {
+  // maximized windows keep their size on scale factor changes
+  let events = scale_factor_change_events(WindowBuilder::new().with_maximized(true), 2.5);
+  assert_resized_after_scale_factor_changed(&events, 2.5);
+}